@@ -0,0 +1,143 @@
+use crate::time::DateTime;
+use malachite::{num::conversion::traits::FromSciString, Rational};
+use rustc_hash::FxHashMap;
+
+/// A currency, named by its ISO 4217 code like `EUR`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Currency(&'static str);
+
+/// Every active ISO 4217 currency code, in alphabetical order.
+const CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHF", "CLP", "CNY", "COP", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+    "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+    "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+    "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+    "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+    "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+    "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB",
+    "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS",
+    "VES", "VND", "VUV", "WST", "XAF", "XCD", "XCG", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+impl Currency {
+    pub fn from_code(code: &str) -> Option<Self> {
+        CODES
+            .binary_search(&code)
+            .ok()
+            .map(|index| Self(CODES[index]))
+    }
+
+    pub const fn code(self) -> &'static str {
+        self.0
+    }
+
+    pub fn codes() -> impl Iterator<Item = &'static str> {
+        CODES.iter().copied()
+    }
+}
+
+impl core::fmt::Display for Currency {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Exchange rates from a file the user keeps up to date, as the amount of each currency that is
+/// worth the same as one unit of a common base currency.
+#[derive(Clone, Debug)]
+pub struct Rates {
+    /// The amount of each currency worth one of the base currency.
+    amounts: FxHashMap<Currency, Rational>,
+    /// The currency the rates are given against, which is worth exactly one of itself.
+    pub base: Currency,
+    /// The day the rates were taken, if the file says so.
+    pub date: Option<DateTime>,
+}
+
+impl Default for Rates {
+    fn default() -> Self {
+        Self {
+            amounts: FxHashMap::default(),
+            base: Currency("EUR"),
+            date: None,
+        }
+    }
+}
+
+impl Rates {
+    /// Parses lines like `USD,1.0712` or `USD = 1.0712`, so both a CSV file and a flat TOML file
+    /// can be read, along with a `date` line for when the rates were taken and a `base` line for
+    /// the currency they are given against, which is `EUR` unless the file says otherwise. Blank
+    /// lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rates = Self::default();
+
+        for (number, line) in text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+        {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once([',', '='])
+                .ok_or_else(|| format!("Line {number} should look like `USD,1.0712`"))?;
+
+            let (key, value) = (key.trim(), value.trim().trim_matches('"'));
+
+            if key.eq_ignore_ascii_case("date") {
+                rates.date = Some(
+                    DateTime::parse(value)
+                        .ok_or_else(|| format!("Line {number} has an invalid date `{value}`"))?,
+                );
+
+                continue;
+            }
+
+            if key.eq_ignore_ascii_case("base") {
+                rates.base = Currency::from_code(value)
+                    .ok_or_else(|| format!("Line {number} has an unknown currency `{value}`"))?;
+
+                continue;
+            }
+
+            let currency = Currency::from_code(key)
+                .ok_or_else(|| format!("Line {number} has an unknown currency `{key}`"))?;
+
+            let rate = Rational::from_sci_string(value)
+                .filter(|rate| *rate > 0u32)
+                .ok_or_else(|| format!("Line {number} has an invalid rate `{value}`"))?;
+
+            rates.amounts.insert(currency, rate);
+        }
+
+        let base = rates.base;
+
+        if rates.amounts.get(&base).is_some_and(|rate| *rate != 1u32) {
+            return Err(format!(
+                "The base currency {base} should have a rate of 1, since the rates are given \
+                 against it"
+            ));
+        }
+
+        rates.amounts.insert(base, Rational::from(1u32));
+
+        Ok(rates)
+    }
+
+    /// The amount of `to` worth the same as `amount` of `from`, unless either has no rate.
+    pub fn convert(&self, amount: &Rational, from: Currency, to: Currency) -> Option<Rational> {
+        Some(amount * self.amounts.get(&to)? / self.amounts.get(&from)?)
+    }
+
+    pub fn contains(&self, currency: Currency) -> bool {
+        self.amounts.contains_key(&currency)
+    }
+}
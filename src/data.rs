@@ -0,0 +1,157 @@
+use crate::time::TimeUnit;
+use malachite::{num::arithmetic::traits::Pow, Rational};
+use std::sync::LazyLock;
+
+/// A unit of information like `MB` or `GiB`, where decimal and binary prefixes are never mixed
+/// up, so a `GB` is always 10⁹ bytes and a `GiB` is always 2³⁰ bytes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DataUnit {
+    prefix: Prefix,
+    bytes: bool,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+enum Prefix {
+    None,
+    /// A power of 1000, where 1 is kilo.
+    Decimal(u8),
+    /// A power of 1024, where 1 is kibi.
+    Binary(u8),
+}
+
+const DECIMAL: [&str; 8] = ["k", "M", "G", "T", "P", "E", "Z", "Y"];
+
+const BINARY: [&str; 8] = ["Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi", "Yi"];
+
+impl Prefix {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        if symbol.is_empty() {
+            return Some(Self::None);
+        }
+
+        let power = |prefixes: [&str; 8]| {
+            (1..)
+                .zip(prefixes)
+                .find(|(_, prefix)| *prefix == symbol)
+                .map(|(power, _)| power)
+        };
+
+        power(DECIMAL)
+            .map(Self::Decimal)
+            .or_else(|| power(BINARY).map(Self::Binary))
+    }
+
+    fn factor(self) -> Rational {
+        match self {
+            Self::None => Rational::from(1u32),
+            Self::Decimal(power) => Rational::from(1000u32).pow(u64::from(power)),
+            Self::Binary(power) => Rational::from(1024u32).pow(u64::from(power)),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Decimal(power) => DECIMAL[usize::from(power) - 1],
+            Self::Binary(power) => BINARY[usize::from(power) - 1],
+        }
+    }
+}
+
+static SYMBOLS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    core::iter::once("")
+        .chain(DECIMAL)
+        .chain(BINARY)
+        .flat_map(|prefix| {
+            [
+                format!("{prefix}B"),
+                format!("{prefix}bit"),
+                format!("{prefix}bps"),
+            ]
+        })
+        .chain(["byte", "bytes", "bits"].map(String::from))
+        .collect()
+});
+
+/// Every symbol an amount of data or a data rate can be written with, without a time unit.
+pub fn symbols() -> impl Iterator<Item = &'static str> {
+    SYMBOLS.iter().map(String::as_str)
+}
+
+impl DataUnit {
+    /// Reads symbols like `B`, `kB`, `MiB`, `bit` and `Gbit`. `KB` is not read, since it is
+    /// used for both 1000 and 1024 bytes.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let (prefix, bytes) = match symbol {
+            "byte" | "bytes" => ("", true),
+            "bits" => ("", false),
+            _ => symbol
+                .strip_suffix("bit")
+                .map(|prefix| (prefix, false))
+                .or_else(|| symbol.strip_suffix('B').map(|prefix| (prefix, true)))?,
+        };
+
+        Some(Self {
+            prefix: Prefix::from_symbol(prefix)?,
+            bytes,
+        })
+    }
+
+    /// The number of bits in one of this unit.
+    pub fn bits(self) -> Rational {
+        let factor = self.prefix.factor();
+
+        if self.bytes {
+            factor * Rational::from(8u32)
+        } else {
+            factor
+        }
+    }
+}
+
+impl core::fmt::Display for DataUnit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.prefix.symbol(),
+            if self.bytes { "B" } else { "bit" }
+        )
+    }
+}
+
+/// A unit of data rate like `Mbit/s` or `MB/min`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RateUnit {
+    pub data: DataUnit,
+    pub time: TimeUnit,
+}
+
+impl RateUnit {
+    pub const fn per_second(data: DataUnit) -> Self {
+        Self {
+            data,
+            time: TimeUnit::Second,
+        }
+    }
+
+    /// Reads symbols like `Mbps`, which are written `Mbit/s`.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let prefix = Prefix::from_symbol(symbol.strip_suffix("bps")?)?;
+
+        Some(Self::per_second(DataUnit {
+            prefix,
+            bytes: false,
+        }))
+    }
+
+    pub fn bits_per_second(self) -> Rational {
+        self.data.bits() / self.time.seconds()
+    }
+}
+
+impl core::fmt::Display for RateUnit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/{}", self.data, self.time.name(true))
+    }
+}
@@ -1,6 +1,12 @@
 use super::{Diag, ErrorSpan};
-use crate::span::Span;
-use chumsky::error::{Rich, RichReason};
+use crate::{lexer::MAX_EXPONENT, span::Span};
+use chumsky::{
+    error::{Rich, RichPattern, RichReason},
+    input::Input,
+    label::LabelError,
+    span::Span as _,
+    util::MaybeRef,
+};
 use codespan_reporting::diagnostic::Severity;
 use core::fmt::Display;
 use core::num::ParseIntError;
@@ -13,12 +19,13 @@ pub enum Error {
         found: Option<String>,
         span: Span,
     },
-    Custom {
-        message: String,
+    UnexpectedCharacter {
+        character: char,
         span: Span,
     },
     UndefinedVariable {
         name: &'static str,
+        suggestion: Option<&'static str>,
         span: Span,
     },
     PrecisionZero(Span),
@@ -26,32 +33,341 @@ pub enum Error {
         span: Span,
         err: ParseIntError,
     },
+    UnknownErrorCode {
+        code: &'static str,
+        span: Span,
+    },
+    InvalidBase(Span),
+    NonInteger {
+        operator: String,
+        span: Span,
+    },
+    DivisionByZero(Span),
+    ShiftOutOfRange(Span),
+    UndefinedFunction {
+        name: &'static str,
+        suggestion: Option<&'static str>,
+        span: Span,
+    },
+    ArgumentCount {
+        name: &'static str,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    NotReal {
+        operator: String,
+        span: Span,
+    },
+    InvalidInterval {
+        operator: String,
+        reason: &'static str,
+        span: Span,
+    },
+    InvalidUncertain {
+        operator: String,
+        reason: &'static str,
+        span: Span,
+    },
+    WrongType {
+        operator: String,
+        expected: &'static str,
+        found: &'static str,
+        span: Span,
+    },
+    LengthMismatch {
+        operator: String,
+        lhs: usize,
+        rhs: usize,
+        span: Span,
+    },
+    InvalidList {
+        operator: String,
+        reason: &'static str,
+        span: Span,
+    },
+    IndexOutOfRange {
+        index: String,
+        length: usize,
+        span: Span,
+    },
+    ArgumentOutOfRange {
+        name: &'static str,
+        expected: &'static str,
+        span: Span,
+    },
+    InvalidMatrix {
+        operator: String,
+        reason: &'static str,
+        span: Span,
+    },
+    ShapeMismatch {
+        operator: String,
+        lhs: String,
+        rhs: String,
+        span: Span,
+    },
+    DateOutOfRange(Span),
+    UnknownTimeZone {
+        name: &'static str,
+        suggestion: Option<&'static str>,
+        span: Span,
+    },
+    NonexistentLocalTime {
+        zone: &'static str,
+        span: Span,
+    },
+    AmbiguousLocalTime {
+        zone: &'static str,
+        earlier: String,
+        later: String,
+        span: Span,
+    },
+    DimensionMismatch {
+        operator: String,
+        lhs: String,
+        rhs: String,
+        span: Span,
+    },
+    MissingExchangeRate {
+        currency: &'static str,
+        /// The base currency of the loaded rates, if any are loaded.
+        base: Option<&'static str>,
+        span: Span,
+    },
+    UnclosedDelimiter {
+        delimiter: char,
+        span: Span,
+    },
+    UnmatchedDelimiter {
+        delimiter: char,
+        span: Span,
+    },
+    UnclosedString(Span),
+    InvalidDate {
+        text: String,
+        span: Span,
+    },
+    OffsetAndZone {
+        text: String,
+        span: Span,
+    },
+    ExponentOutOfRange {
+        exponent: String,
+        span: Span,
+    },
+    AmbiguousPercent(Span),
+    UnknownUnit {
+        name: String,
+        suggestion: String,
+        span: Span,
+    },
+    MisplacedSeparator {
+        digits: String,
+        span: Span,
+    },
 }
 
 impl Diag for Error {
-    #[allow(clippy::match_same_arms)]
-    fn message(&self) -> Cow<str> {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UndefinedVariable { .. } => "P0001",
+            Self::ExpectedFound { .. } => "P0002",
+            Self::UnexpectedCharacter { .. } => "P0003",
+            Self::PrecisionZero(_) => "P0004",
+            Self::InvalidPrecision { .. } => "P0005",
+            Self::UnknownErrorCode { .. } => "P0006",
+            Self::InvalidBase(_) => "P0007",
+            Self::NonInteger { .. } => "P0008",
+            Self::DivisionByZero(_) => "P0009",
+            Self::ShiftOutOfRange(_) => "P0010",
+            Self::UndefinedFunction { .. } => "P0011",
+            Self::ArgumentCount { .. } => "P0012",
+            Self::NotReal { .. } => "P0013",
+            Self::InvalidInterval { .. } => "P0014",
+            Self::InvalidUncertain { .. } => "P0015",
+            Self::WrongType { .. } => "P0016",
+            Self::LengthMismatch { .. } => "P0017",
+            Self::InvalidList { .. } => "P0018",
+            Self::IndexOutOfRange { .. } => "P0019",
+            Self::ArgumentOutOfRange { .. } => "P0020",
+            Self::InvalidMatrix { .. } => "P0021",
+            Self::ShapeMismatch { .. } => "P0022",
+            Self::DateOutOfRange(_) => "P0023",
+            Self::UnknownTimeZone { .. } => "P0024",
+            Self::NonexistentLocalTime { .. } => "P0025",
+            Self::AmbiguousLocalTime { .. } => "P0026",
+            Self::DimensionMismatch { .. } => "P0027",
+            Self::MissingExchangeRate { .. } => "P0028",
+            Self::UnclosedDelimiter { .. } => "P0029",
+            Self::UnmatchedDelimiter { .. } => "P0030",
+            Self::UnclosedString(_) => "P0031",
+            Self::InvalidDate { .. } => "P0032",
+            Self::OffsetAndZone { .. } => "P0033",
+            Self::ExponentOutOfRange { .. } => "P0034",
+            Self::AmbiguousPercent(_) => "P0035",
+            Self::UnknownUnit { .. } => "P0036",
+            Self::MisplacedSeparator { .. } => "P0037",
+        }
+    }
+
+    #[allow(clippy::match_same_arms, clippy::too_many_lines)]
+    fn message(&self) -> Cow<'_, str> {
         match self {
             Self::ExpectedFound {
                 expected,
                 found,
                 span: _,
             } => format!(
-                "Expected one of {}, but found {}",
-                expected.join(", "),
-                found.as_deref().unwrap_or("end of file")
+                "Expected {}, but found {}",
+                join_expected(expected),
+                found
+                    .as_deref()
+                    .map_or_else(|| "end of input".into(), |found| format!("`{found}`"))
             )
             .into(),
-            Self::Custom { message, span: _ } => message.into(),
-            Self::UndefinedVariable { name, span: _ } => {
-                format!("Undefined variable `{name}`").into()
+            Self::UnexpectedCharacter { character, span: _ } => {
+                format!("Unexpected character `{character}`").into()
             }
+            Self::UndefinedVariable {
+                name,
+                suggestion: _,
+                span: _,
+            } => format!("Undefined variable `{name}`").into(),
             Self::PrecisionZero(_) => "Precision must be greater than zero".into(),
             Self::InvalidPrecision { span: _, err } => format!("Invalid precision: {err}").into(),
+            Self::UnknownErrorCode { code, span: _ } => {
+                format!("Unknown error code `{code}`").into()
+            }
+            Self::InvalidBase(_) => "Invalid base".into(),
+            Self::NonInteger { operator, span: _ } => {
+                format!("`{operator}` can only be applied to integers").into()
+            }
+            Self::DivisionByZero(_) => "Division by zero".into(),
+            Self::ShiftOutOfRange(_) => "Shift amount out of range".into(),
+            Self::UndefinedFunction {
+                name,
+                suggestion: _,
+                span: _,
+            } => format!("Undefined function `{name}`").into(),
+            Self::ArgumentCount {
+                name,
+                expected,
+                found,
+                span: _,
+            } => format!(
+                "`{name}` takes {expected} argument{}, but {found} {} given",
+                if *expected == 1 { "" } else { "s" },
+                if *found == 1 { "was" } else { "were" }
+            )
+            .into(),
+            Self::NotReal { operator, span: _ } => {
+                format!("`{operator}` can only be applied to real numbers").into()
+            }
+            Self::InvalidInterval {
+                operator,
+                reason: _,
+                span: _,
+            } => format!("`{operator}` cannot be applied to this interval").into(),
+            Self::InvalidUncertain {
+                operator,
+                reason: _,
+                span: _,
+            } => format!("`{operator}` cannot be applied to this measurement").into(),
+            Self::WrongType {
+                operator,
+                expected,
+                found,
+                span: _,
+            } => format!("`{operator}` expects {expected}, but found {found}").into(),
+            Self::LengthMismatch {
+                operator,
+                lhs: _,
+                rhs: _,
+                span: _,
+            } => format!("`{operator}` cannot be applied to lists of different lengths").into(),
+            Self::InvalidList {
+                operator,
+                reason: _,
+                span: _,
+            } => format!("`{operator}` cannot be applied to this list").into(),
+            Self::IndexOutOfRange {
+                index,
+                length,
+                span: _,
+            } => format!(
+                "Index {index} is out of range for a list of {length} element{}",
+                if *length == 1 { "" } else { "s" }
+            )
+            .into(),
+            Self::ArgumentOutOfRange {
+                name,
+                expected,
+                span: _,
+            } => format!("`{name}` expects {expected}").into(),
+            Self::InvalidMatrix {
+                operator,
+                reason: _,
+                span: _,
+            } => format!("`{operator}` cannot be applied to this matrix").into(),
+            Self::ShapeMismatch {
+                operator,
+                lhs: _,
+                rhs: _,
+                span: _,
+            } => format!("`{operator}` cannot be applied to values of these shapes").into(),
+            Self::DateOutOfRange(_) => "Date out of range".into(),
+            Self::UnknownTimeZone {
+                name,
+                suggestion: _,
+                span: _,
+            } => format!("Unknown time zone `{name}`").into(),
+            Self::NonexistentLocalTime { zone, span: _ } => {
+                format!("This time does not exist in `{zone}`").into()
+            }
+            Self::AmbiguousLocalTime { zone, .. } => {
+                format!("This time happens twice in `{zone}`").into()
+            }
+            Self::DimensionMismatch {
+                operator,
+                lhs,
+                rhs,
+                span: _,
+            } => format!("`{operator}` cannot combine {lhs} and {rhs}").into(),
+            Self::MissingExchangeRate { currency, .. } => {
+                format!("No exchange rate for {currency}").into()
+            }
+            Self::UnclosedDelimiter { delimiter, span: _ } => {
+                format!("Unclosed delimiter `{delimiter}`").into()
+            }
+            Self::UnmatchedDelimiter { delimiter, span: _ } => {
+                format!("Unmatched closing delimiter `{delimiter}`").into()
+            }
+            Self::UnclosedString(_) => "Unclosed string".into(),
+            Self::InvalidDate { text, span: _ } => format!("Invalid date `{text}`").into(),
+            Self::OffsetAndZone { text, span: _ } => {
+                format!("`{text}` cannot have both an offset and a time zone").into()
+            }
+            Self::ExponentOutOfRange { exponent, span: _ } => {
+                format!("Exponent `{exponent}` is out of range").into()
+            }
+            Self::AmbiguousPercent(_) => {
+                "`% -` is ambiguous, write `a mod -b` for a remainder or `(a%) - b` for a \
+                 percentage"
+                    .into()
+            }
+            Self::UnknownUnit {
+                name,
+                suggestion,
+                span: _,
+            } => format!("Unknown unit `{name}`, {suggestion}").into(),
+            Self::MisplacedSeparator { digits, span: _ } => {
+                format!("Misplaced `_` in `{digits}`").into()
+            }
         }
     }
 
-    #[allow(clippy::match_same_arms)]
+    #[allow(clippy::match_same_arms, clippy::too_many_lines)]
     fn spans(&self) -> Vec<ErrorSpan> {
         match self {
             Self::ExpectedFound {
@@ -59,22 +375,235 @@ impl Diag for Error {
                 found,
                 span,
             } => vec![ErrorSpan::primary(
-                format!("Found {}", found.as_deref().unwrap_or("end of file")),
+                found.as_deref().map_or_else(
+                    || "Unexpected end of input".into(),
+                    |found| format!("Unexpected `{found}`"),
+                ),
                 *span,
             )],
-            Self::Custom { message: _, span } => vec![ErrorSpan::primary_span(*span)],
-            Self::UndefinedVariable { name: _, span } => {
-                vec![ErrorSpan::primary("This variable is undefined", *span)]
+            Self::UnexpectedCharacter { character: _, span } => {
+                vec![ErrorSpan::primary(
+                    "This character is not part of any token",
+                    *span,
+                )]
+            }
+            Self::UndefinedVariable {
+                name: _,
+                suggestion,
+                span,
+            } => {
+                let mut spans = vec![ErrorSpan::primary("This variable is undefined", *span)];
+
+                if let Some(suggestion) = suggestion {
+                    spans.push(ErrorSpan::secondary(
+                        format!("Did you mean `{suggestion}`?"),
+                        *span,
+                    ));
+                }
+
+                spans
             }
             Self::PrecisionZero(span) => vec![ErrorSpan::primary_span(*span)],
             Self::InvalidPrecision { span, err: _ } => vec![ErrorSpan::primary_span(*span)],
+            Self::UnknownErrorCode { code: _, span } => {
+                vec![ErrorSpan::primary("This error code does not exist", *span)]
+            }
+            Self::InvalidBase(span) => vec![ErrorSpan::primary_span(*span)],
+            Self::NonInteger { operator: _, span } => {
+                vec![ErrorSpan::primary("This value is not an integer", *span)]
+            }
+            Self::DivisionByZero(span) => vec![ErrorSpan::primary("This value is zero", *span)],
+            Self::ShiftOutOfRange(span) => vec![ErrorSpan::primary_span(*span)],
+            Self::UndefinedFunction {
+                name: _,
+                suggestion,
+                span,
+            } => {
+                let mut spans = vec![ErrorSpan::primary("This function is undefined", *span)];
+
+                if let Some(suggestion) = suggestion {
+                    spans.push(ErrorSpan::secondary(
+                        format!("Did you mean `{suggestion}`?"),
+                        *span,
+                    ));
+                }
+
+                spans
+            }
+            Self::ArgumentCount {
+                name: _,
+                expected: _,
+                found: _,
+                span,
+            } => vec![ErrorSpan::primary_span(*span)],
+            Self::NotReal { operator: _, span } => {
+                vec![ErrorSpan::primary("This value is a complex number", *span)]
+            }
+            Self::InvalidInterval {
+                operator: _,
+                reason,
+                span,
+            }
+            | Self::InvalidUncertain {
+                operator: _,
+                reason,
+                span,
+            }
+            | Self::InvalidList {
+                operator: _,
+                reason,
+                span,
+            }
+            | Self::InvalidMatrix {
+                operator: _,
+                reason,
+                span,
+            } => vec![ErrorSpan::primary(*reason, *span)],
+            Self::WrongType {
+                operator: _,
+                expected: _,
+                found,
+                span,
+            } => vec![ErrorSpan::primary(format!("This is {found}"), *span)],
+            Self::LengthMismatch {
+                operator: _,
+                lhs,
+                rhs,
+                span,
+            } => vec![ErrorSpan::primary(
+                format!("These lists have {lhs} and {rhs} elements"),
+                *span,
+            )],
+            Self::IndexOutOfRange {
+                index: _,
+                length: _,
+                span,
+            } => vec![ErrorSpan::primary("This index is out of range", *span)],
+            Self::ArgumentOutOfRange {
+                name: _,
+                expected: _,
+                span,
+            } => vec![ErrorSpan::primary("This argument is out of range", *span)],
+            Self::ShapeMismatch {
+                operator: _,
+                lhs,
+                rhs,
+                span,
+            } => vec![ErrorSpan::primary(
+                format!("These are {lhs} and {rhs}"),
+                *span,
+            )],
+            Self::DateOutOfRange(span) => vec![ErrorSpan::primary(
+                "This date is outside of the supported years",
+                *span,
+            )],
+            Self::UnknownTimeZone {
+                name: _,
+                suggestion,
+                span,
+            } => {
+                let mut spans = vec![ErrorSpan::primary("This time zone is unknown", *span)];
+
+                if let Some(suggestion) = suggestion {
+                    spans.push(ErrorSpan::secondary(
+                        format!("Did you mean `{suggestion}`?"),
+                        *span,
+                    ));
+                }
+
+                spans
+            }
+            Self::NonexistentLocalTime { zone: _, span } => vec![ErrorSpan::primary(
+                "The clocks skip this time when daylight saving time starts",
+                *span,
+            )],
+            Self::AmbiguousLocalTime { span, .. } => vec![ErrorSpan::primary(
+                "The clocks pass this time again when daylight saving time ends",
+                *span,
+            )],
+            Self::DimensionMismatch {
+                operator: _,
+                lhs,
+                rhs,
+                span,
+            } => vec![ErrorSpan::primary(
+                format!("These are in {lhs} and {rhs}"),
+                *span,
+            )],
+            Self::MissingExchangeRate {
+                currency,
+                base: _,
+                span,
+            } => vec![ErrorSpan::primary(
+                format!("This cannot be converted to or from {currency}"),
+                *span,
+            )],
+            Self::UnclosedDelimiter { delimiter: _, span } => {
+                vec![ErrorSpan::primary("This is never closed", *span)]
+            }
+            Self::UnmatchedDelimiter { delimiter: _, span } => {
+                vec![ErrorSpan::primary("This closes nothing", *span)]
+            }
+            Self::UnclosedString(span) => {
+                vec![ErrorSpan::primary("This string has no closing `\"`", *span)]
+            }
+            Self::InvalidDate { text: _, span } => {
+                vec![ErrorSpan::primary("This date does not exist", *span)]
+            }
+            Self::OffsetAndZone { text: _, span }
+            | Self::ExponentOutOfRange { exponent: _, span } => {
+                vec![ErrorSpan::primary_span(*span)]
+            }
+            Self::AmbiguousPercent(span) => vec![ErrorSpan::primary(
+                "This could be a remainder or a percentage",
+                *span,
+            )],
+            Self::UnknownUnit {
+                name: _,
+                suggestion: _,
+                span,
+            } => vec![ErrorSpan::primary("This is not a unit", *span)],
+            Self::MisplacedSeparator { digits: _, span } => vec![ErrorSpan::primary(
+                "Each `_` needs a digit on both sides",
+                *span,
+            )],
         }
     }
 
     fn notes(&self) -> Vec<String> {
         match self {
-            Self::ExpectedFound { .. } | Self::Custom { .. } | Self::PrecisionZero(_) => vec![],
-            Self::UndefinedVariable { name, span: _ } => {
+            Self::ExpectedFound { .. }
+            | Self::UnexpectedCharacter { .. }
+            | Self::UnclosedDelimiter { .. }
+            | Self::UnmatchedDelimiter { .. }
+            | Self::UnclosedString(_)
+            | Self::AmbiguousPercent(_)
+            | Self::UnknownUnit { .. }
+            | Self::PrecisionZero(_)
+            | Self::NonInteger { .. }
+            | Self::DivisionByZero(_)
+            | Self::ArgumentCount { .. }
+            | Self::NotReal { .. }
+            | Self::InvalidInterval { .. }
+            | Self::InvalidUncertain { .. }
+            | Self::WrongType { .. }
+            | Self::LengthMismatch { .. }
+            | Self::InvalidList { .. }
+            | Self::ArgumentOutOfRange { .. }
+            | Self::InvalidMatrix { .. } => vec![],
+            Self::ShapeMismatch { .. } => vec![
+                "Multiplying needs as many columns on the left as rows on the right, and adding \
+                 needs the same shape"
+                    .into(),
+            ],
+            Self::IndexOutOfRange { .. } => {
+                vec!["Lists are indexed from 0, and negative indices count from the end".into()]
+            }
+            Self::UndefinedVariable {
+                name,
+                suggestion: _,
+                span: _,
+            } => {
                 vec![
                     format!("Consider assigning a value to `{name}`:"),
                     format!("{name} = <value>"),
@@ -83,6 +612,64 @@ impl Diag for Error {
             Self::InvalidPrecision { span: _, err: _ } => {
                 vec!["The precision must be a natural number".into()]
             }
+            Self::UnknownErrorCode { code: _, span: _ } => {
+                vec!["Error codes look like `P0001` or `W0001`".into()]
+            }
+            Self::InvalidBase(_) => vec!["The base must be a whole number from 2 to 36".into()],
+            Self::UndefinedFunction { .. } => {
+                vec!["Use `help` to list the available functions".into()]
+            }
+            Self::DateOutOfRange(_) => vec!["Dates can be from the year 1 to 9999".into()],
+            Self::UnknownTimeZone { .. } => vec![
+                "Time zones are written like `America/New_York`, `Europe/Berlin` or `UTC`".into(),
+            ],
+            Self::NonexistentLocalTime { .. } => {
+                vec!["Write a time after the clocks move forward, or one with an offset".into()]
+            }
+            Self::AmbiguousLocalTime {
+                zone: _,
+                earlier,
+                later,
+                span: _,
+            } => vec![format!(
+                "Write `{earlier}` for the first one, or `{later}` for the second"
+            )],
+            Self::DimensionMismatch { .. } => {
+                vec!["Convert amounts to one currency first, like `120 EUR to USD`".into()]
+            }
+            Self::MissingExchangeRate {
+                currency,
+                base: Some(base),
+                span: _,
+            } => vec![format!(
+                "Add a line like `{currency},1.25` to the rates file, with the amount of \
+                 {currency} worth 1 {base}"
+            )],
+            Self::MissingExchangeRate { base: None, .. } => {
+                vec!["Load exchange rates by starting with `--rates <file>`".into()]
+            }
+            Self::ShiftOutOfRange(_) => {
+                vec![format!(
+                    "Numbers can be shifted by at most {} bits",
+                    1 << 20
+                )]
+            }
+            Self::InvalidDate { .. } => vec![
+                "Dates are written like `2026-10-17`, with a month from 1 to 12 and a day that \
+                 is in that month"
+                    .into(),
+            ],
+            Self::OffsetAndZone { .. } => vec![
+                "Write either an offset like `2026-10-17T09:30+02:00` or a zone like \
+                 `2026-10-17T09:30[Europe/Berlin]`"
+                    .into(),
+            ],
+            Self::ExponentOutOfRange { .. } => {
+                vec![format!("Exponents can be at most {MAX_EXPONENT}")]
+            }
+            Self::MisplacedSeparator { .. } => {
+                vec!["Write digits in groups like `1_000_000`".into()]
+            }
         }
     }
 
@@ -91,18 +678,528 @@ impl Diag for Error {
     }
 }
 
-pub fn convert(error: &Rich<impl Display, Span, &str>) -> Vec<Error> {
+#[allow(clippy::too_many_lines)]
+pub(super) fn explain(code: &str) -> Option<&'static str> {
+    Some(match code.to_ascii_uppercase().as_str() {
+        "P0001" => {
+            "A variable was used before a value was assigned to it.
+
+Names have to be assigned with `=` before they can be used in an expression:
+
+    >> radius * 2
+    error[P0001]: Undefined variable `radius`
+
+    >> radius = 3
+    >> radius * 2
+    6
+
+Use `vars` to list every name that currently has a value."
+        }
+        "P0002" => {
+            "The input could not be parsed, because a token appeared where it was not expected.
+
+The error describes what would have been valid at that position:
+
+    >> 1 +
+    error[P0002]: Expected an expression, but found end of input
+
+Complete the expression, or remove the stray token:
+
+    >> 1 + 2
+    3"
+        }
+        "P0003" => {
+            "The input contains a character that is not part of any token.
+
+    >> 1 $ 2
+    error[P0003]: Unexpected character `$`
+
+Remove the character, or check the help for the operator you meant."
+        }
+        "P0004" => {
+            "The precision was set to zero.
+
+`precision` sets the number of significant digits used when printing numbers, so it has to be at least one:
+
+    >> precision 0
+    error[P0004]: Precision must be greater than zero
+
+    >> precision 5
+    Set precision to: 5
+
+Use `fullprecision` to print numbers with as many digits as needed instead."
+        }
+        "P0005" => {
+            "The precision is not a natural number.
+
+`precision` only accepts whole numbers that are greater than zero:
+
+    >> precision 2.5
+    error[P0005]: Invalid precision: invalid digit found in string
+
+    >> precision 3
+    Set precision to: 3"
+        }
+        "P0006" => {
+            "`explain` was given an error code that does not exist.
+
+Error codes consist of the letter `P` for errors or `W` for warnings, followed by four digits:
+
+    >> explain P9999
+    error[P0006]: Unknown error code `P9999`
+
+    >> explain P0001"
+        }
+        "P0007" => {
+            "A number was converted to a base that cannot be displayed.
+
+Results can be shown in any base from 2 to 36, where digits above 9 are written as letters:
+
+    >> 255 to base 40
+    error[P0007]: Invalid base
+
+    >> 255 to base 16
+    0xff
+
+    >> 255 to base 7
+    513 (base 7)
+
+`to bin`, `to oct`, `to dec` and `to hex` are shorthands for the common bases."
+        }
+        "P0008" => {
+            "An integer operator was applied to a number with a fractional part.
+
+`mod`, `%`, `div`, `&`, `|`, `xor`, `~`, `<<` and `>>` are only defined for integers:
+
+    >> 7.5 mod 2
+    error[P0008]: `mod` can only be applied to integers
+
+    >> 7 mod 2
+    1"
+        }
+        "P0009" => {
+            "A number was divided by zero.
+
+This applies to `/` as well as to the integer operators `mod`, `%` and `div`:
+
+    >> 1 / 0
+    error[P0009]: Division by zero"
+        }
+        "P0010" => {
+            "A number was shifted by too many bits.
+
+The shift amount of `<<` and `>>` has to be an integer between -1048576 and 1048576:
+
+    >> 1 << 2000000
+    error[P0010]: Shift amount out of range
+
+    >> 1 << 20
+    1048576"
+        }
+        "P0011" => {
+            "A function was called that does not exist.
+
+Only the built-in functions can be called, and they are listed by `help`:
+
+    >> sqr(16)
+    error[P0011]: Undefined function `sqr`
+
+    >> sqrt(16)
+    4"
+        }
+        "P0012" => {
+            "A function was called with the wrong number of arguments.
+
+Every function takes a fixed number of arguments, separated by commas:
+
+    >> sqrt(16, 2)
+    error[P0012]: `sqrt` takes 1 argument, but 2 were given
+
+    >> sqrt(16)
+    4"
+        }
+        "P0013" => {
+            "An operation that is only defined on the real numbers was applied to a complex number.
+
+Percentages and `as % of` need real numbers, so use `abs` or the real part instead:
+
+    >> (3 + 4 * i) as % of 10
+    error[P0013]: `as % of` can only be applied to real numbers
+
+    >> abs(3 + 4 * i) as % of 10
+    50%"
+        }
+        "P0014" => {
+            "An operation was applied to an interval for which it has no guaranteed bounds.
+
+Dividing by an interval that contains zero, or taking the square root of one that contains negative numbers, would not give a single interval:
+
+    >> 1 / (0 ± 1)
+    error[P0014]: `/` cannot be applied to this interval
+
+    >> 1 / (2 ± 1)
+    0.6666666666666667 ∈ [0.3333333333333333, 1]"
+        }
+        "P0015" => {
+            "An operation was applied to a measurement for which its uncertainty cannot be propagated.
+
+Uncertainties are propagated to first order, which fails where the derivative is undefined, and they cannot be mixed with intervals:
+
+    >> sqrt(0 +- 0.1)
+    error[P0015]: `sqrt` cannot be applied to this measurement
+
+    >> sqrt(4 +- 0.1)
+    2.000 ± 0.025"
+        }
+        "P0016" => {
+            "A value of the wrong type was used, like a boolean in arithmetic or a number with `and`.
+
+Comparisons produce `true` or `false`, which can only be combined with `and`, `or` and `not`:
+
+    >> (1 < 2) + 1
+    error[P0016]: `+` expects a number, but found a boolean
+
+    >> 1 < 2 and 2 < 3
+    true"
+        }
+        "P0017" => {
+            "An operator was applied to two lists with different numbers of elements.
+
+Operators are applied element by element, so both lists need the same length, while a single number is applied to every element:
+
+    >> [1, 2, 3] + [10, 20]
+    error[P0017]: `+` cannot be applied to lists of different lengths
+
+    >> [1, 2, 3] + 10
+    [11, 12, 13]"
+        }
+        "P0018" => {
+            "A list could not be used, because it is empty or has too many elements.
+
+Functions like `mean` need at least one element, and ranges are limited in size:
+
+    >> mean([])
+    error[P0018]: `mean` cannot be applied to this list
+
+    >> mean([1, 2, 6])
+    3"
+        }
+        "P0019" => {
+            "A list was indexed past its end.
+
+Lists are indexed from 0, and negative indices count back from the end:
+
+    >> [10, 20, 30][3]
+    error[P0019]: Index 3 is out of range for a list of 3 elements
+
+    >> [10, 20, 30][-1]
+    30"
+        }
+        "P0020" => {
+            "A function was given an argument outside of the values it accepts.
+
+The error says which values are accepted:
+
+    >> percentile([1, 2, 3], 150)
+    error[P0020]: `percentile` expects a percentile from 0 to 100
+
+    >> percentile([1, 2, 3], 50)
+    2"
+        }
+        "P0021" => {
+            "A matrix operation was applied to a matrix that does not support it.
+
+Determinants, inverses and `solve` need square matrices, and inverses and `solve` also need the matrix to be invertible:
+
+    >> inv([[1, 2], [2, 4]])
+    error[P0021]: `inv` cannot be applied to this matrix
+
+    >> inv([[1, 2], [3, 4]])
+    [[-2, 1], [1.5, -0.5]]"
+        }
+        "P0022" => {
+            "Two matrices or vectors were combined, but their shapes do not fit together.
+
+A product needs as many columns on the left as there are rows on the right, while adding and subtracting need the same shape:
+
+    >> [[1, 2], [3, 4]] * [[1, 2, 3]]
+    error[P0022]: `*` cannot be applied to values of these shapes
+
+    >> [[1, 2, 3]] * [[1], [2], [3]]
+    [[14]]"
+        }
+        "P0023" => {
+            "A date was moved outside of the supported years.
+
+Dates are written with four-digit years, so they can be from the year 1 to 9999:
+
+    >> 9999-12-31 + 1 day
+    error[P0023]: Date out of range
+
+    >> 9999-12-31 - 1 day
+    9999-12-30"
+        }
+        "P0024" => {
+            "A time zone was used that is not in the IANA time zone database.
+
+Time zones are named like in the database, or by just their city, and older names like `Europe/Kiev` still work. Every change to a zone's offset is known, so past dates get the offset that was in use then:
+
+    >> 2026-10-17T09:30Z in \"Europe/Berlin\"
+    2026-10-17 11:30:00 CEST (UTC+02:00)
+
+    >> 2026-10-17T09:30Z to tz tokyo
+    2026-10-17 18:30:00 JST (UTC+09:00)
+
+    >> 1990-07-01T12:00Z in \"Europe/Moscow\"
+    1990-07-01 16:00:00 MSD (UTC+04:00)
+
+    >> now in \"Mars/Olympus_Mons\"
+    error[P0024]: Unknown time zone `Mars/Olympus_Mons`
+
+Write the time with an offset like `+02:00` for places that are not in the database."
+        }
+        "P0025" => {
+            "A local time was written that is skipped when the clocks move forward.
+
+When daylight saving time starts, the clocks jump ahead an hour, so the times in between never happen:
+
+    >> 2026-03-08T02:30[America/New_York]
+    error[P0025]: This time does not exist in `America/New_York`
+
+    >> 2026-03-08T03:30[America/New_York]
+    2026-03-08 07:30:00 UTC"
+        }
+        "P0026" => {
+            "A local time was written that happens twice when the clocks move back.
+
+When daylight saving time ends, the clocks go back an hour, so the times in between happen once with each offset:
+
+    >> 2026-11-01T01:30[America/New_York]
+    error[P0026]: This time happens twice in `America/New_York`
+
+    >> 2026-11-01T01:30-04:00
+    2026-11-01 05:30:00 UTC
+
+Write the time with the offset of the one you mean instead."
+        }
+        "P0027" => {
+            "Amounts in different currencies were combined without converting them.
+
+Adding, subtracting and comparing amounts needs them to be in the same currency, and dividing one by another only gives a number when they are:
+
+    >> 120 EUR + 30 USD
+    error[P0027]: `+` cannot combine EUR and USD
+
+    >> 120 EUR + 25 EUR
+    145 EUR
+
+Convert a result to another currency with `to`, like `120 EUR to USD`."
+        }
+        "P0028" => {
+            "An amount was converted to or from a currency without an exchange rate.
+
+Exchange rates are never fetched from the network. They are read from a file given with `--rates <file>`, with a line for each currency saying how much of it one unit of the base currency is worth, and optionally the day they were taken. The base is EUR unless a `base` line names another one, and it is always worth 1:
+
+    date,2026-10-16
+    base,EUR
+    USD,1.0712
+    GBP,0.8351
+
+Lines like `USD = 1.0712` work too, so the file can be written as TOML:
+
+    >> 120 EUR to USD
+    128.544 USD
+
+    >> 120 EUR to CHF
+    error[P0028]: No exchange rate for CHF"
+        }
+        "P0029" => {
+            "A parenthesis, bracket or brace was opened but never closed.
+
+    >> (1 + 2
+    error[P0029]: Unclosed delimiter `(`
+
+    >> (1 + 2) * 3
+    9"
+        }
+        "P0030" => {
+            "A closing parenthesis, bracket or brace was found without a matching opening one.
+
+    >> 1 + 2)
+    error[P0030]: Unmatched closing delimiter `)`
+
+Remove it, or add the opening one where the group starts."
+        }
+        "P0031" => {
+            "A string was started with `\"` but never closed.
+
+Strings are used for time zone names and cannot span lines:
+
+    >> now in \"Europe/Berlin
+    error[P0031]: Unclosed string
+
+    >> now in \"Europe/Berlin\"
+    2026-10-17 11:30:00 CEST (UTC+02:00)"
+        }
+        "P0032" => {
+            "A date or time was written that does not exist.
+
+Dates are written like `2026-10-17`, with a month from 1 to 12, a day that is in that month and a year from 1 to 9999:
+
+    >> 2026-02-30
+    error[P0032]: Invalid date `2026-02-30`
+
+    >> 2028-02-29
+    2028-02-29"
+        }
+        "P0033" => {
+            "A time was written with both an offset and a time zone.
+
+An offset already fixes the moment, so there is nothing left for the zone to decide:
+
+    >> 2026-10-17T09:30+02:00[Europe/Berlin]
+    error[P0033]: `2026-10-17T09:30+02:00` cannot have both an offset and a time zone
+
+Write either `2026-10-17T09:30+02:00` or `2026-10-17T09:30[Europe/Berlin]`."
+        }
+        "P0034" => {
+            "A number was written with an exponent that is too large.
+
+Numbers are stored exactly, so an exponent like `1e1000000` would need millions of digits. Exponents can be at most 100000:
+
+    >> 1e1000000
+    error[P0034]: Exponent `1000000` is out of range"
+        }
+        "P0035" => {
+            "A `%` was followed by `-`, which could mean two different things.
+
+`%` is both the remainder operator and the percent sign, and both readings of `a % -b` are reasonable:
+
+    >> 10 % -3
+    error[P0035]: `% -` is ambiguous, write `a mod -b` for a remainder or `(a%) - b` for a percentage
+
+    >> 10 mod -3
+    -2
+
+    >> (10%) - 3
+    -2.9"
+        }
+        "P0036" => {
+            "A number was followed by a name that is close to a unit, but is not one.
+
+Units are case sensitive, and decimal and binary prefixes are never mixed up:
+
+    >> 5 mni
+    error[P0036]: Unknown unit `mni`, did you mean `min`?
+
+    >> 1 KB
+    error[P0036]: Unknown unit `KB`, write `kB` for 1000 bytes or `KiB` for 1024 bytes"
+        }
+        "P0037" => {
+            "A number has a `_` that does not sit between two digits.
+
+`_` only groups digits to make long numbers easier to read, so it cannot end a number or come twice in a row:
+
+    >> 1__000
+    error[P0037]: Misplaced `_` in `1__000`
+
+    >> 1_000
+    1000"
+        }
+        _ => return None,
+    })
+}
+
+fn join_expected(expected: &[String]) -> String {
+    match expected {
+        [] => "something else".into(),
+        [expected] => expected.clone(),
+        [init @ .., last] => format!("{} or {last}", init.join(", ")),
+    }
+}
+
+/// An error found while lexing or parsing, which is either one of chumsky's errors about the
+/// input that was expected there, or an [`Error`] with its own code.
+#[derive(Debug)]
+pub enum SyntaxError<'a, T, L> {
+    Expected(Rich<'a, T, Span, L>),
+    Invalid(Error),
+}
+
+impl<'a, I, L> chumsky::error::Error<'a, I> for SyntaxError<'a, I::Token, L>
+where
+    I: Input<'a, Span = Span>,
+    I::Token: PartialEq,
+    L: PartialEq,
+{
+    fn expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
+        expected: E,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: Span,
+    ) -> Self {
+        Self::Expected(<Rich<_, _, _> as chumsky::error::Error<I>>::expected_found(
+            expected, found, span,
+        ))
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Expected(lhs), Self::Expected(rhs)) => {
+                Self::Expected(<Rich<_, _, _> as chumsky::error::Error<I>>::merge(lhs, rhs))
+            }
+            // Errors with their own code are only ever emitted next to a successful parse, so
+            // they say more than any input that was expected instead.
+            (invalid @ Self::Invalid(_), _) | (_, invalid @ Self::Invalid(_)) => invalid,
+        }
+    }
+}
+
+impl<'a, I, L> LabelError<'a, I, L> for SyntaxError<'a, I::Token, L>
+where
+    I: Input<'a, Span = Span>,
+    I::Token: PartialEq,
+    L: PartialEq,
+{
+    fn label_with(&mut self, label: L) {
+        if let Self::Expected(rich) = self {
+            <Rich<_, _, _> as LabelError<I, L>>::label_with(rich, label);
+        }
+    }
+
+    fn in_context(&mut self, label: L, span: Span) {
+        if let Self::Expected(rich) = self {
+            <Rich<_, _, _> as LabelError<I, L>>::in_context(rich, label, span);
+        }
+    }
+}
+
+pub fn convert(error: &SyntaxError<impl Display, &str>) -> Vec<Error> {
     fn convert_inner(reason: &RichReason<impl Display, &str>, span: Span) -> Vec<Error> {
         match reason {
-            RichReason::ExpectedFound { expected, found } => vec![Error::ExpectedFound {
-                expected: expected.iter().map(ToString::to_string).collect(),
-                found: found.as_ref().map(|f| f.to_string()),
-                span,
-            }],
-            RichReason::Custom(message) => vec![Error::Custom {
-                message: message.to_owned(),
-                span,
-            }],
+            RichReason::ExpectedFound { expected, found } => {
+                let mut patterns = Vec::<String>::new();
+
+                for pattern in expected {
+                    let pattern = match pattern {
+                        RichPattern::Token(token) => format!("`{}`", &**token),
+                        RichPattern::Label(label) => (*label).to_string(),
+                        RichPattern::EndOfInput => "end of input".into(),
+                    };
+
+                    if !patterns.contains(&pattern) {
+                        patterns.push(pattern);
+                    }
+                }
+
+                vec![Error::ExpectedFound {
+                    expected: patterns,
+                    found: found.as_ref().map(|f| f.to_string()),
+                    span: if found.is_some() { span } else { span.to_end() },
+                }]
+            }
+            RichReason::Custom(_) => {
+                unreachable!("errors with a message are emitted as `SyntaxError::Invalid`")
+            }
             RichReason::Many(reasons) => reasons
                 .iter()
                 .flat_map(|r| convert_inner(r, span))
@@ -110,5 +1207,8 @@ pub fn convert(error: &Rich<impl Display, Span, &str>) -> Vec<Error> {
         }
     }
 
-    convert_inner(error.reason(), *error.span())
+    match error {
+        SyntaxError::Expected(error) => convert_inner(error.reason(), *error.span()),
+        SyntaxError::Invalid(error) => vec![error.clone()],
+    }
 }
@@ -4,14 +4,21 @@ use std::borrow::Cow;
 
 pub mod error;
 pub mod report;
+pub mod suggest;
+pub mod warning;
 
 pub trait Diag {
-    fn message(&self) -> Cow<str>;
+    fn code(&self) -> &'static str;
+    fn message(&self) -> Cow<'_, str>;
     fn spans(&self) -> Vec<ErrorSpan>;
     fn notes(&self) -> Vec<String>;
     fn kind(&self) -> Severity;
 }
 
+pub fn explain(code: &str) -> Option<&'static str> {
+    error::explain(code).or_else(|| warning::explain(code))
+}
+
 #[derive(Debug)]
 pub struct ErrorSpan {
     message: Option<String>,
@@ -36,13 +43,13 @@ impl ErrorSpan {
         }
     }
 
-    // fn secondary(message: impl Into<String>, span: Span) -> Self {
-    //     Self {
-    //         message: Some(message.into()),
-    //         span,
-    //         label_style: LabelStyle::Secondary,
-    //     }
-    // }
+    fn secondary(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: Some(message.into()),
+            span,
+            label_style: LabelStyle::Secondary,
+        }
+    }
 
     // const fn secondary_span(span: Span) -> Self {
     //     Self {
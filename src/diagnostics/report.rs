@@ -1,9 +1,11 @@
 use super::Diag;
+use crate::json::Json;
 use chumsky::span::Span as _;
-use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
 
 pub fn report(diagnostic: &dyn Diag) -> Diagnostic<usize> {
     Diagnostic::new(diagnostic.kind())
+        .with_code(diagnostic.code())
         .with_message(diagnostic.message())
         .with_labels(
             diagnostic
@@ -26,3 +28,50 @@ pub fn report(diagnostic: &dyn Diag) -> Diagnostic<usize> {
         )
         .with_notes(diagnostic.notes())
 }
+
+pub fn json(diagnostic: &dyn Diag) -> Json {
+    Json::Object(vec![
+        ("code", Json::string(diagnostic.code())),
+        ("message", Json::string(diagnostic.message())),
+        (
+            "severity",
+            Json::string(match diagnostic.kind() {
+                Severity::Bug => "bug",
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note => "note",
+                Severity::Help => "help",
+            }),
+        ),
+        (
+            "spans",
+            Json::Array(
+                diagnostic
+                    .spans()
+                    .into_iter()
+                    .map(|error_span| {
+                        Json::Object(vec![
+                            ("start", Json::Number(error_span.span.start())),
+                            ("end", Json::Number(error_span.span.end())),
+                            (
+                                "style",
+                                Json::string(match error_span.label_style {
+                                    LabelStyle::Primary => "primary",
+                                    LabelStyle::Secondary => "secondary",
+                                }),
+                            ),
+                            (
+                                "message",
+                                error_span.message.map_or(Json::Null, Json::String),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "notes",
+            Json::Array(diagnostic.notes().into_iter().map(Json::String).collect()),
+        ),
+    ])
+}
@@ -0,0 +1,167 @@
+use super::{Diag, ErrorSpan};
+use crate::{evaluator::MAX_DIGITS, span::Span};
+use codespan_reporting::diagnostic::Severity;
+use std::borrow::Cow;
+
+#[derive(Clone, Debug)]
+pub enum Warning {
+    PrecisionLoss { precision: Option<u64>, span: Span },
+    ShadowedConstant { name: &'static str, span: Span },
+    ExchangeRates { date: Option<String>, span: Span },
+    ShadowedUnit { name: &'static str, span: Span },
+}
+
+impl Diag for Warning {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::PrecisionLoss { .. } => "W0001",
+            Self::ShadowedConstant { .. } => "W0002",
+            Self::ExchangeRates { .. } => "W0003",
+            Self::ShadowedUnit { .. } => "W0004",
+        }
+    }
+
+    fn message(&self) -> Cow<'_, str> {
+        match self {
+            Self::PrecisionLoss { precision, span: _ } => precision.map_or_else(
+                || "This result has been truncated".into(),
+                |precision| {
+                    format!("This result has been rounded to {precision} significant digits").into()
+                },
+            ),
+            Self::ShadowedConstant { name, span: _ } => {
+                format!("Assigning to `{name}` shadows a built-in constant").into()
+            }
+            Self::ExchangeRates {
+                date: Some(date),
+                span: _,
+            } => format!("Converted with exchange rates as of {date}").into(),
+            Self::ExchangeRates {
+                date: None,
+                span: _,
+            } => "Converted with exchange rates of an unknown date".into(),
+            Self::ShadowedUnit { name, span: _ } => {
+                format!("Assigning to `{name}` shadows a unit").into()
+            }
+        }
+    }
+
+    fn spans(&self) -> Vec<ErrorSpan> {
+        match self {
+            Self::PrecisionLoss { precision: _, span } => {
+                vec![ErrorSpan::primary(
+                    "This value cannot be displayed exactly",
+                    *span,
+                )]
+            }
+            Self::ShadowedConstant { name: _, span } => {
+                vec![ErrorSpan::primary(
+                    "This name is a built-in constant",
+                    *span,
+                )]
+            }
+            Self::ExchangeRates { date: _, span } => {
+                vec![ErrorSpan::primary("This amount was converted", *span)]
+            }
+            Self::ShadowedUnit { name: _, span } => {
+                vec![ErrorSpan::primary("This name is also a unit", *span)]
+            }
+        }
+    }
+
+    fn notes(&self) -> Vec<String> {
+        match self {
+            Self::PrecisionLoss {
+                precision: Some(_),
+                span: _,
+            } => vec!["Use `precision <p>` to display more digits".into()],
+            Self::PrecisionLoss {
+                precision: None,
+                span: _,
+            } => vec![format!(
+                "Only the first {MAX_DIGITS} digits after the point are displayed"
+            )],
+            Self::ShadowedConstant { name, span: _ } => {
+                vec![format!(
+                    "The built-in value of `{name}` is no longer available"
+                )]
+            }
+            Self::ExchangeRates { date: Some(_), .. } => {
+                vec!["Update the `--rates` file for newer rates".into()]
+            }
+            Self::ExchangeRates { date: None, .. } => {
+                vec!["Add a line like `date,2026-10-16` to the `--rates` file".into()]
+            }
+            Self::ShadowedUnit { name, span: _ } => {
+                vec![format!(
+                    "`2 {name}` is still read as the unit, while `{name}` on its own is this variable"
+                )]
+            }
+        }
+    }
+
+    fn kind(&self) -> Severity {
+        match self {
+            Self::PrecisionLoss { .. } | Self::ExchangeRates { .. } => Severity::Note,
+            Self::ShadowedConstant { .. } | Self::ShadowedUnit { .. } => Severity::Warning,
+        }
+    }
+}
+
+pub(super) fn explain(code: &str) -> Option<&'static str> {
+    Some(match code.to_ascii_uppercase().as_str() {
+        "W0001" => {
+            "A result could not be displayed exactly at the current precision.
+
+Numbers are stored exactly, but they are rounded to the current precision when printed:
+
+    >> precision 3
+    >> 1/3
+    0.333
+    note[W0001]: This result has been rounded to 3 significant digits
+
+The exact value is still used in later calculations. Raise the precision to display more digits, or use `warnings off` to silence this note."
+        }
+        "W0002" => {
+            "A built-in constant was overwritten by an assignment.
+
+Constants such as `pi`, `tau` and `e` are ordinary names, so assigning to them replaces their value for the rest of the session:
+
+    >> pi = 3
+    warning[W0002]: Assigning to `pi` shadows a built-in constant
+
+    >> pi * 2
+    6
+
+Pick a different name to keep the constant available."
+        }
+        "W0003" => {
+            "An amount was converted with the exchange rates from the `--rates` file.
+
+The rates are only as current as the file, so every conversion says when they were taken:
+
+    >> 120 EUR to USD
+    128.544 USD
+    note[W0003]: Converted with exchange rates as of 2026-10-16
+
+Give the day with a line like `date,2026-10-16` in the file, and update the file for newer rates."
+        }
+        "W0004" => {
+            "A variable was given the name of a unit.
+
+A unit after a number is still read as the unit, but the name on its own now refers to the variable, which is easy to mix up:
+
+    >> min = 3
+    warning[W0004]: Assigning to `min` shadows a unit
+
+    >> 2 min
+    2 min
+
+    >> min * 2
+    6
+
+Pick a different name to keep the two apart."
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,161 @@
+use super::functions::{atan, pi, sqrt};
+use core::cmp::Ordering;
+use malachite::{
+    num::{
+        arithmetic::traits::{Abs, Sign},
+        basic::traits::{One, Zero},
+    },
+    Rational,
+};
+
+/// A complex number with exact rational parts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Complex {
+    pub re: Rational,
+    pub im: Rational,
+}
+
+impl Complex {
+    pub const I: Self = Self {
+        re: Rational::ZERO,
+        im: Rational::ONE,
+    };
+
+    pub const fn real(re: Rational) -> Self {
+        Self {
+            re,
+            im: Rational::ZERO,
+        }
+    }
+
+    pub fn conj(&self) -> Self {
+        Self {
+            re: self.re.clone(),
+            im: -&self.im,
+        }
+    }
+
+    /// The squared magnitude, which unlike [`Self::abs`] is always exact.
+    pub fn norm(&self) -> Rational {
+        &self.re * &self.re + &self.im * &self.im
+    }
+
+    pub fn abs(&self) -> Rational {
+        sqrt(&self.norm())
+    }
+
+    /// The angle to the positive real axis in radians, between -pi and pi, which like
+    /// [`Self::abs`] is rounded when it is not rational.
+    pub fn arg(&self) -> Rational {
+        match (self.re.sign(), self.im.sign()) {
+            (Ordering::Greater, _) => atan(&(&self.im / &self.re)),
+            (Ordering::Less, Ordering::Less) => atan(&(&self.im / &self.re)) - pi(),
+            (Ordering::Less, _) => atan(&(&self.im / &self.re)) + pi(),
+            (Ordering::Equal, Ordering::Greater) => pi() >> 1u32,
+            (Ordering::Equal, Ordering::Less) => -(pi() >> 1u32),
+            (Ordering::Equal, Ordering::Equal) => Rational::ZERO,
+        }
+    }
+
+    /// The principal square root, whose real part is never negative.
+    pub fn sqrt(&self) -> Self {
+        if self.im == 0u32 {
+            return if self.re >= 0u32 {
+                Self::real(sqrt(&self.re))
+            } else {
+                Self {
+                    re: Rational::ZERO,
+                    im: sqrt(&-&self.re),
+                }
+            };
+        }
+
+        let abs = self.abs();
+        let two = Rational::from(2u32);
+
+        let re = sqrt(&((&abs + &self.re) / &two));
+        let im = sqrt(&((&abs - &self.re) / &two));
+
+        Self {
+            re,
+            im: if self.im.sign().is_lt() { -im } else { im },
+        }
+    }
+
+    pub fn display_with(&self, f: impl Fn(&Rational) -> String) -> String {
+        let imaginary = |im: &Rational| {
+            if *im == 1u32 {
+                "i".into()
+            } else {
+                format!("{}i", f(im))
+            }
+        };
+
+        let im = imaginary(&(&self.im).abs());
+
+        match (self.re == 0u32, self.im < 0u32) {
+            (true, false) => im,
+            (true, true) => format!("-{im}"),
+            (false, false) => format!("{} + {im}", f(&self.re)),
+            (false, true) => format!("{} - {im}", f(&self.re)),
+        }
+    }
+}
+
+impl core::ops::Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl core::ops::Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl core::ops::Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            re: &self.re * &rhs.re - &self.im * &rhs.im,
+            im: &self.re * &rhs.im + &self.im * &rhs.re,
+        }
+    }
+}
+
+impl core::ops::Div for Complex {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let norm = rhs.norm();
+        let product = self * rhs.conj();
+
+        Self {
+            re: product.re / &norm,
+            im: product.im / norm,
+        }
+    }
+}
+
+impl core::ops::Neg for Complex {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use malachite::{
+    num::{arithmetic::traits::DivMod, conversion::traits::ToStringBase},
+    Natural, Rational,
+};
+use rustc_hash::FxHashMap;
+
+#[derive(Clone, Debug)]
+pub struct Expansion {
+    negative: bool,
+    integer: String,
+    fraction: Vec<u8>,
+    period: Vec<u8>,
+    truncated: bool,
+}
+
+impl Expansion {
+    pub fn new(rational: &Rational, base: u8, max_digits: usize) -> Self {
+        let (numerator, denominator) = rational.numerator_and_denominator_ref();
+        let (integer, mut remainder) = numerator.div_mod(denominator);
+
+        let base_natural = Natural::from(base);
+
+        let mut seen = FxHashMap::default();
+        let mut digits = vec![];
+
+        let mut expansion = Self {
+            negative: *rational < 0u32,
+            integer: integer.to_string_base(base),
+            fraction: vec![],
+            period: vec![],
+            truncated: false,
+        };
+
+        while remainder != 0u32 {
+            if let Some(start) = seen.get(&remainder) {
+                expansion.period = digits.split_off(*start);
+                expansion.fraction = digits;
+
+                return expansion;
+            }
+
+            if digits.len() == max_digits {
+                expansion.truncated = true;
+
+                break;
+            }
+
+            seen.insert(remainder.clone(), digits.len());
+
+            let (digit, next) = (remainder * &base_natural).div_mod(denominator);
+
+            digits.push(u8::try_from(&digit).unwrap());
+            remainder = next;
+        }
+
+        expansion.fraction = digits;
+
+        expansion
+    }
+
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn display(&self, prefix: &str) -> String {
+        let digits = |digits: &[u8]| {
+            digits
+                .iter()
+                .map(|digit| char::from_digit(u32::from(*digit), 36).unwrap())
+                .collect::<String>()
+        };
+
+        let mut string = format!(
+            "{}{prefix}{}",
+            if self.negative { "-" } else { "" },
+            self.integer
+        );
+
+        if !self.fraction.is_empty() || !self.period.is_empty() || self.truncated {
+            string.push('.');
+            string.push_str(&digits(&self.fraction));
+        }
+
+        if !self.period.is_empty() {
+            string.push('(');
+            string.push_str(&digits(&self.period));
+            string.push(')');
+        }
+
+        if self.truncated {
+            string.push('…');
+        }
+
+        string
+    }
+}
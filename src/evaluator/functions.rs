@@ -0,0 +1,384 @@
+use super::{
+    complex::Complex, describe, describe_list, integer, interval::Interval, list, matrix,
+    matrix::Matrix, number_theory, real, statistics, vector, wrong_type, Value,
+};
+use crate::{diagnostics::error::Error, diagnostics::suggest::closest, span::Spanned};
+use chumsky::span::Span as _;
+use malachite::{
+    num::{
+        arithmetic::traits::{Abs, CheckedRoot, CheckedSqrt, Floor, FloorRoot, FloorSqrt},
+        basic::traits::Zero,
+    },
+    Natural, Rational,
+};
+use malachite_q::arithmetic::traits::Approximate;
+
+/// The number of bits after the point kept by roots and angles that are not exact.
+const ROOT_BITS: u64 = 256;
+
+/// The extra bits kept while summing a series, so that rounding each term does not change the
+/// bits that are kept in the end.
+const GUARD_BITS: u64 = 32;
+
+/// Every built-in function along with the number of arguments it takes.
+pub const FUNCTIONS: &[(&str, usize)] = &[
+    ("abs", 1),
+    ("approx", 2),
+    ("arg", 1),
+    ("binomial", 2),
+    ("conj", 1),
+    ("det", 1),
+    ("factor", 1),
+    ("gcd", 2),
+    ("geomean", 1),
+    ("harmean", 1),
+    ("inv", 1),
+    ("isprime", 1),
+    ("lcm", 2),
+    ("len", 1),
+    ("linreg", 2),
+    ("max", 1),
+    ("mean", 1),
+    ("median", 1),
+    ("min", 1),
+    ("mode", 1),
+    ("modinv", 2),
+    ("modpow", 3),
+    ("nextprime", 1),
+    ("percentile", 2),
+    ("product", 1),
+    ("pstdev", 1),
+    ("pvariance", 1),
+    ("rank", 1),
+    ("solve", 2),
+    ("sqrt", 1),
+    ("stdev", 1),
+    ("sum", 1),
+    ("totient", 1),
+    ("transpose", 1),
+    ("variance", 1),
+];
+
+pub fn call(name: Spanned<&'static str>, args: &[Spanned<Value>]) -> Result<Value, Error> {
+    let arity = check(name, args)?;
+
+    Ok(match (name.0, args) {
+        (
+            "abs" | "arg" | "conj" | "factor" | "isprime" | "nextprime" | "sqrt" | "totient",
+            [Spanned(Value::List(list), span)],
+        ) => Value::List(
+            list.iter()
+                .map(|value| call(name, &[Spanned::new(value.clone(), *span)]))
+                .collect::<Result<_, _>>()?,
+        ),
+        ("len", [x]) => Value::Number(Rational::from(list(name.0, x)?.len())),
+        ("sum", [x]) => statistics::sum(name, x)?,
+        ("product", [x]) => statistics::product(name, x)?,
+        ("mean", [x]) => statistics::mean(name, x)?,
+        ("median", [x]) => statistics::median(name.0, x)?,
+        ("mode", [x]) => statistics::mode(name.0, x)?,
+        ("percentile", [x, p]) => statistics::percentile(name.0, x, p)?,
+        ("min", [x]) => statistics::extreme(name.0, x, false)?,
+        ("max", [x]) => statistics::extreme(name.0, x, true)?,
+        ("variance", [x]) => statistics::variance(name.0, x, false)?,
+        ("pvariance", [x]) => statistics::variance(name.0, x, true)?,
+        ("stdev", [x]) => statistics::stdev(name.0, x, false)?,
+        ("pstdev", [x]) => statistics::stdev(name.0, x, true)?,
+        ("geomean", [x]) => statistics::geomean(name.0, x)?,
+        ("harmean", [x]) => statistics::harmean(name.0, x)?,
+        ("linreg", [xs, ys]) => statistics::linreg(name.0, xs, ys)?,
+        ("det", [x]) => Value::Number(square(name.0, x)?.det()),
+        ("inv", [x]) => Value::Matrix(
+            square(name.0, x)?
+                .inverse()
+                .ok_or_else(|| singular(name.0, x))?,
+        ),
+        ("rank", [x]) => Value::Number(Rational::from(matrix(name.0, x)?.rank())),
+        ("transpose", [x]) => Value::Matrix(matrix(name.0, x)?.transpose()),
+        ("solve", [a, b]) => solve(name.0, a, b)?,
+        ("approx", [x, d]) => approx(name.0, x, d)?,
+        (
+            "binomial" | "factor" | "gcd" | "isprime" | "lcm" | "modinv" | "modpow" | "nextprime"
+            | "totient",
+            _,
+        ) => number_theory::call(name.0, args)?,
+        ("arg" | "sqrt", [x]) if matches!(x.0, Value::Matrix(_)) => {
+            return Err(wrong_type(name.0, x, "a number"))
+        }
+        ("abs", [x]) => match &x.0 {
+            Value::Number(number) => Value::Number(number.abs()),
+            Value::Percent(percent) => Value::Percent(percent.abs()),
+            Value::Complex(complex) => Value::Number(complex.abs()),
+            Value::Interval(interval) => Value::from_interval(interval.abs()),
+            Value::Uncertain(uncertain) => Value::Uncertain(uncertain.abs()),
+            Value::Matrix(matrix) => Value::Matrix(matrix.map(|entry| entry.abs())),
+            Value::Bool(_) | Value::List(_) => {
+                unreachable!("booleans are rejected and lists are mapped above")
+            }
+            Value::Factors(_) => {
+                unreachable!("factorizations are multiplied out before they are used")
+            }
+            _ => {
+                unreachable!("times and amounts are rejected before functions are called")
+            }
+        },
+        ("arg", [x]) => match &x.0 {
+            Value::Interval(interval) if interval.contains_zero() => {
+                return Err(Error::InvalidInterval {
+                    operator: name.0.into(),
+                    reason: "This interval contains zero",
+                    span: x.1,
+                })
+            }
+            Value::Interval(interval) => Value::Number(Complex::real(interval.lower.clone()).arg()),
+            value => Value::Number(value.to_complex().arg()),
+        },
+        ("conj", [x]) => match &x.0 {
+            Value::Complex(complex) => Value::Complex(complex.conj()),
+            value => value.clone(),
+        },
+        ("sqrt", [x]) => match &x.0 {
+            Value::Interval(interval) if interval.lower < 0u32 => {
+                return Err(Error::InvalidInterval {
+                    operator: name.0.into(),
+                    reason: "This interval contains negative numbers",
+                    span: x.1,
+                })
+            }
+            Value::Interval(interval) => Value::from_interval(Interval {
+                lower: sqrt(&interval.lower),
+                upper: sqrt_upper(&interval.upper),
+            }),
+            Value::Uncertain(uncertain) => {
+                Value::from_uncertain(uncertain.sqrt().ok_or_else(|| Error::InvalidUncertain {
+                    operator: name.0.into(),
+                    reason: "The uncertainty cannot be propagated through this value",
+                    span: x.1,
+                })?)
+            }
+            value => Value::from_complex(value.to_complex().sqrt()),
+        },
+        _ => unreachable!("`{}` is listed with {arity} arguments", name.0),
+    })
+}
+
+/// Checks that the function exists and is given the right number of arguments, none of which are
+/// booleans, times or amounts of money, and returns that number.
+fn check(name: Spanned<&'static str>, args: &[Spanned<Value>]) -> Result<usize, Error> {
+    let Some(&(_, arity)) = FUNCTIONS.iter().find(|(function, _)| *function == name.0) else {
+        return Err(Error::UndefinedFunction {
+            name: name.0,
+            suggestion: closest(name.0, FUNCTIONS.iter().map(|(function, _)| *function)),
+            span: name.1,
+        });
+    };
+
+    if args.len() != arity {
+        return Err(Error::ArgumentCount {
+            name: name.0,
+            expected: arity,
+            found: args.len(),
+            span: name.1,
+        });
+    }
+
+    if let Some(arg) = args.iter().find(|arg| {
+        matches!(
+            arg.0,
+            Value::Bool(_)
+                | Value::DateTime(_)
+                | Value::Duration(_)
+                | Value::Money(..)
+                | Value::Data(..)
+                | Value::DataRate(..)
+        )
+    }) {
+        return Err(wrong_type(name.0, arg, "a number"));
+    }
+
+    Ok(arity)
+}
+
+fn square<'a>(name: &'static str, x: &'a Spanned<Value>) -> Result<&'a Matrix, Error> {
+    let matrix = matrix(name, x)?;
+
+    if matrix.is_square() {
+        Ok(matrix)
+    } else {
+        Err(Error::InvalidMatrix {
+            operator: name.into(),
+            reason: "This matrix is not square",
+            span: x.1,
+        })
+    }
+}
+
+fn singular(name: &'static str, x: &Spanned<Value>) -> Error {
+    Error::InvalidMatrix {
+        operator: name.into(),
+        reason: "This matrix is singular",
+        span: x.1,
+    }
+}
+
+/// Solves `a * x = b`, where `b` is a matrix or a list for a column vector.
+fn solve(name: &'static str, a: &Spanned<Value>, b: &Spanned<Value>) -> Result<Value, Error> {
+    let matrix = square(name, a)?;
+    let size = matrix.shape().0;
+
+    let mismatch = |rhs| Error::ShapeMismatch {
+        operator: name.into(),
+        lhs: describe(matrix),
+        rhs,
+        span: a.1.union(b.1),
+    };
+
+    let (rhs, column) = match &b.0 {
+        Value::Matrix(rhs) if rhs.shape().0 != size => return Err(mismatch(describe(rhs))),
+        Value::Matrix(rhs) => (rhs.clone(), false),
+        _ => {
+            let vector = vector(name, b)?;
+
+            if vector.len() != size {
+                return Err(mismatch(describe_list(vector.len())));
+            }
+
+            let rows = vector.into_iter().map(|entry| vec![entry]).collect();
+
+            (Matrix::new(rows).unwrap(), true)
+        }
+    };
+
+    let solution = matrix.solve(&rhs).ok_or_else(|| singular(name, a))?;
+
+    Ok(if column {
+        Value::List(
+            solution
+                .entries()
+                .iter()
+                .cloned()
+                .map(Value::Number)
+                .collect(),
+        )
+    } else {
+        Value::Matrix(solution)
+    })
+}
+
+/// The closest fraction to `x` whose denominator is at most `d`, preferring the smaller
+/// denominator when two are equally close.
+fn approx(name: &'static str, x: &Spanned<Value>, d: &Spanned<Value>) -> Result<Value, Error> {
+    let max_denominator = Natural::try_from(integer(name, d)?)
+        .ok()
+        .filter(|d| *d != 0u32)
+        .ok_or(Error::ArgumentOutOfRange {
+            name,
+            expected: "a positive maximum denominator",
+            span: d.1,
+        })?;
+
+    Ok(Value::Number(
+        real(name, x)?.clone().approximate(&max_denominator),
+    ))
+}
+
+/// The square root of a non-negative number, which is exact whenever the result is rational and
+/// rounded down otherwise.
+pub fn sqrt(x: &Rational) -> Rational {
+    x.checked_sqrt().unwrap_or_else(|| {
+        let scaled = Natural::try_from((x << (2 * ROOT_BITS)).floor()).unwrap();
+
+        Rational::from(scaled.floor_sqrt()) >> ROOT_BITS
+    })
+}
+
+/// The `n`th root of a non-negative number, which like [`sqrt`] is exact whenever the result is
+/// rational and rounded down otherwise.
+pub fn root(x: &Rational, n: u64) -> Rational {
+    x.clone().checked_root(n).unwrap_or_else(|| {
+        let scaled = Natural::try_from((x << (n * ROOT_BITS)).floor()).unwrap();
+
+        Rational::from(scaled.floor_root(n)) >> ROOT_BITS
+    })
+}
+
+/// Like [`sqrt`], but rounded up when the result is not rational.
+fn sqrt_upper(x: &Rational) -> Rational {
+    x.checked_sqrt()
+        .unwrap_or_else(|| sqrt(x) + (Rational::from(1u32) >> ROOT_BITS))
+}
+
+/// The arctangent in radians, rounded down to `ROOT_BITS` bits after the point.
+pub fn atan(x: &Rational) -> Rational {
+    let bits = ROOT_BITS + GUARD_BITS;
+
+    if *x < 0u32 {
+        return -atan(&-x);
+    }
+
+    // The series converges quickly below 1, so larger numbers use atan(x) = pi/2 - atan(1/x).
+    let angle = if *x > 1u32 {
+        (pi_with(bits) >> 1u32) - atan_reduced(&(Rational::from(1u32) / x), bits)
+    } else {
+        atan_reduced(x, bits)
+    };
+
+    round_down(&angle, ROOT_BITS)
+}
+
+/// Pi, rounded down to `ROOT_BITS` bits after the point.
+pub fn pi() -> Rational {
+    round_down(&pi_with(ROOT_BITS + GUARD_BITS), ROOT_BITS)
+}
+
+/// Pi from Machin's formula, pi = 16 atan(1/5) - 4 atan(1/239), with `bits` bits after the point.
+fn pi_with(bits: u64) -> Rational {
+    let atan =
+        |denominator: u32| atan_series(&(Rational::from(1u32) / Rational::from(denominator)), bits);
+
+    (atan(5) << 4u32) - (atan(239) << 2u32)
+}
+
+/// The arctangent of a number between 0 and 1, after halving the angle three times with
+/// tan(a/2) = tan(a) / (1 + sqrt(1 + tan(a)²)) so that the series needs fewer terms.
+fn atan_reduced(x: &Rational, bits: u64) -> Rational {
+    let one = Rational::from(1u32);
+    let mut x = x.clone();
+
+    for _ in 0..3 {
+        x = round_down(&(&x / (&one + sqrt(&(&one + &x * &x)))), bits);
+    }
+
+    atan_series(&x, bits) << 3u32
+}
+
+/// The Taylor series x - x³/3 + x⁵/5 - ... of the arctangent of a number between 0 and 1, with
+/// `bits` bits after the point.
+fn atan_series(x: &Rational, bits: u64) -> Rational {
+    let square = round_down(&(x * x), bits);
+    let mut power = x.clone();
+    let mut sum = Rational::ZERO;
+
+    for n in (1u32..).step_by(2) {
+        if power == 0u32 {
+            break;
+        }
+
+        let term = &power / Rational::from(n);
+
+        if n % 4 == 1 {
+            sum += term;
+        } else {
+            sum -= term;
+        }
+
+        power = round_down(&(power * &square), bits);
+    }
+
+    sum
+}
+
+/// Rounds down to `bits` bits after the point, which keeps the terms of a series small.
+fn round_down(x: &Rational, bits: u64) -> Rational {
+    Rational::from((x << bits).floor()) >> bits
+}
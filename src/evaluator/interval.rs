@@ -0,0 +1,118 @@
+use malachite::{num::arithmetic::traits::Abs, Rational};
+
+/// A closed interval with exact rational bounds, where `lower <= upper`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Interval {
+    pub lower: Rational,
+    pub upper: Rational,
+}
+
+impl Interval {
+    pub fn point(value: Rational) -> Self {
+        Self {
+            lower: value.clone(),
+            upper: value,
+        }
+    }
+
+    /// The interval of every value at most `radius` away from `center`.
+    pub fn around(center: &Rational, radius: &Rational) -> Self {
+        let radius = radius.abs();
+
+        Self {
+            lower: center - &radius,
+            upper: center + radius,
+        }
+    }
+
+    pub fn midpoint(&self) -> Rational {
+        (&self.lower + &self.upper) / Rational::from(2u32)
+    }
+
+    /// The largest absolute value in the interval.
+    pub fn magnitude(&self) -> Rational {
+        (&self.lower).abs().max((&self.upper).abs())
+    }
+
+    pub fn contains_zero(&self) -> bool {
+        self.lower <= 0u32 && self.upper >= 0u32
+    }
+
+    pub fn abs(&self) -> Self {
+        if self.lower >= 0u32 {
+            self.clone()
+        } else if self.upper <= 0u32 {
+            -self.clone()
+        } else {
+            Self {
+                lower: Rational::from(0u32),
+                upper: self.magnitude(),
+            }
+        }
+    }
+
+    /// Divides by `rhs`, unless it contains zero.
+    pub fn checked_div(self, rhs: &Self) -> Option<Self> {
+        if rhs.contains_zero() {
+            return None;
+        }
+
+        Some(
+            self * Self {
+                lower: Rational::from(1u32) / &rhs.upper,
+                upper: Rational::from(1u32) / &rhs.lower,
+            },
+        )
+    }
+}
+
+impl core::ops::Add for Interval {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            lower: self.lower + rhs.lower,
+            upper: self.upper + rhs.upper,
+        }
+    }
+}
+
+impl core::ops::Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            lower: self.lower - rhs.upper,
+            upper: self.upper - rhs.lower,
+        }
+    }
+}
+
+impl core::ops::Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let products = [
+            &self.lower * &rhs.lower,
+            &self.lower * &rhs.upper,
+            &self.upper * &rhs.lower,
+            &self.upper * &rhs.upper,
+        ];
+
+        Self {
+            lower: products.iter().min().unwrap().clone(),
+            upper: products.iter().max().unwrap().clone(),
+        }
+    }
+}
+
+impl core::ops::Neg for Interval {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            lower: -self.upper,
+            upper: -self.lower,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use malachite::{
+    num::basic::traits::{One, Zero},
+    Rational,
+};
+
+/// A matrix with exact rational entries, stored row by row, which always has at least one row
+/// and one column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    columns: usize,
+    entries: Vec<Rational>,
+}
+
+impl Matrix {
+    /// Builds a matrix from its rows, unless there are none or they differ in length.
+    pub fn new(rows: Vec<Vec<Rational>>) -> Option<Self> {
+        let columns = rows.first()?.len();
+
+        if columns == 0 || rows.iter().any(|row| row.len() != columns) {
+            return None;
+        }
+
+        Some(Self {
+            rows: rows.len(),
+            columns,
+            entries: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn identity(size: usize) -> Self {
+        Self {
+            rows: size,
+            columns: size,
+            entries: (0..size * size)
+                .map(|i| {
+                    if i % (size + 1) == 0 {
+                        Rational::ONE
+                    } else {
+                        Rational::ZERO
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    pub const fn is_square(&self) -> bool {
+        self.rows == self.columns
+    }
+
+    pub fn row(&self, row: usize) -> &[Rational] {
+        &self.entries[row * self.columns..(row + 1) * self.columns]
+    }
+
+    pub fn entries(&self) -> &[Rational] {
+        &self.entries
+    }
+
+    pub fn map(&self, f: impl Fn(&Rational) -> Rational) -> Self {
+        Self {
+            rows: self.rows,
+            columns: self.columns,
+            entries: self.entries.iter().map(f).collect(),
+        }
+    }
+
+    /// Combines the entries of two matrices of the same shape.
+    pub fn zip_with(&self, other: &Self, f: impl Fn(&Rational, &Rational) -> Rational) -> Self {
+        Self {
+            rows: self.rows,
+            columns: self.columns,
+            entries: self
+                .entries
+                .iter()
+                .zip(&other.entries)
+                .map(|(lhs, rhs)| f(lhs, rhs))
+                .collect(),
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self {
+            rows: self.columns,
+            columns: self.rows,
+            entries: (0..self.columns)
+                .flat_map(|column| (0..self.rows).map(move |row| (row, column)))
+                .map(|(row, column)| self.row(row)[column].clone())
+                .collect(),
+        }
+    }
+
+    /// The matrix product, where `other` has as many rows as `self` has columns.
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut entries = Vec::with_capacity(self.rows * other.columns);
+
+        for row in 0..self.rows {
+            for column in 0..other.columns {
+                entries.push(
+                    self.row(row)
+                        .iter()
+                        .enumerate()
+                        .map(|(i, entry)| entry * &other.row(i)[column])
+                        .sum(),
+                );
+            }
+        }
+
+        Self {
+            rows: self.rows,
+            columns: other.columns,
+            entries,
+        }
+    }
+
+    /// The product with a column vector, which has as many entries as `self` has columns.
+    pub fn mul_vector(&self, vector: &[Rational]) -> Vec<Rational> {
+        (0..self.rows)
+            .map(|row| self.row(row).iter().zip(vector).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().reduce(self.columns).0
+    }
+
+    /// The determinant of a square matrix.
+    pub fn det(&self) -> Rational {
+        self.clone().reduce(self.columns).1
+    }
+
+    /// The inverse of a square matrix, unless it is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        self.solve(&Self::identity(self.rows))
+    }
+
+    /// The matrix `x` with `self * x == rhs` for a square matrix, unless it is singular.
+    pub fn solve(&self, rhs: &Self) -> Option<Self> {
+        let mut augmented = Self {
+            rows: self.rows,
+            columns: self.columns + rhs.columns,
+            entries: (0..self.rows)
+                .flat_map(|row| self.row(row).iter().chain(rhs.row(row)).cloned())
+                .collect(),
+        };
+
+        let (rank, _) = augmented.reduce(self.columns);
+
+        (rank == self.rows).then(|| Self {
+            rows: self.rows,
+            columns: rhs.columns,
+            entries: (0..self.rows)
+                .flat_map(|row| augmented.row(row)[self.columns..].to_vec())
+                .collect(),
+        })
+    }
+
+    /// Brings the first `columns` columns into reduced row echelon form with Gauss-Jordan
+    /// elimination, returning the rank and, for a square matrix, the determinant.
+    fn reduce(&mut self, columns: usize) -> (usize, Rational) {
+        let mut rank = 0;
+        let mut det = Rational::ONE;
+
+        for column in 0..columns {
+            let Some(pivot) =
+                (rank..self.rows).find(|&row| self.entries[row * self.columns + column] != 0u32)
+            else {
+                det = Rational::ZERO;
+                continue;
+            };
+
+            if pivot != rank {
+                self.swap_rows(pivot, rank);
+                det = -det;
+            }
+
+            let scale = self.entries[rank * self.columns + column].clone();
+            det *= &scale;
+
+            for entry in &mut self.entries[rank * self.columns..(rank + 1) * self.columns] {
+                *entry /= &scale;
+            }
+
+            let pivot_row = self.row(rank).to_vec();
+
+            for row in (0..self.rows).filter(|&row| row != rank) {
+                let factor = self.entries[row * self.columns + column].clone();
+
+                if factor != 0u32 {
+                    for (entry, pivot_entry) in self.entries
+                        [row * self.columns..(row + 1) * self.columns]
+                        .iter_mut()
+                        .zip(&pivot_row)
+                    {
+                        *entry -= &factor * pivot_entry;
+                    }
+                }
+            }
+
+            rank += 1;
+        }
+
+        (rank, det)
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for column in 0..self.columns {
+            self.entries
+                .swap(a * self.columns + column, b * self.columns + column);
+        }
+    }
+}
@@ -1,37 +1,112 @@
 use crate::{
-    diagnostics::error::Error,
-    parser::ast::{BinaryOp, Expression, Statement, UnaryOp},
+    currency::{Currency, Rates},
+    data::{DataUnit, RateUnit},
+    diagnostics::{
+        error::Error,
+        explain,
+        suggest::{closest, units},
+        warning::Warning,
+    },
+    json::Json,
+    lexer::token::Radix,
+    parser::ast::{BinaryOp, Expression, Identifier, IntegerWidth, Statement, Target, UnaryOp},
+    span::{Span, Spanned},
+    time::{
+        self,
+        zone::{Local, Zone},
+        DateTime, TimeUnit,
+    },
 };
-use core::ops::ControlFlow;
+use chumsky::span::Span as _;
+use complex::Complex;
+use core::{cell::Cell, ops::ControlFlow};
+use digits::Expansion;
+use interval::Interval;
 use malachite::{
     num::{
+        arithmetic::traits::{Abs, DivMod, Mod, PowerOf2},
         basic::traits::Zero,
-        conversion::{string::options::ToSciOptions, traits::ToSci},
+        conversion::{
+            string::options::{SciSizeOptions, ToSciOptions},
+            traits::ToSci,
+        },
     },
-    Rational,
+    rounding_modes::RoundingMode,
+    Integer, Rational,
 };
+use malachite_q::conversion::traits::{ContinuedFraction, Convergents};
+use matrix::Matrix;
+use number_theory::Factors;
 use owo_colors::OwoColorize;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::borrow::Cow;
+use uncertain::Uncertain;
+
+mod complex;
+mod digits;
+mod functions;
+mod interval;
+mod matrix;
+mod number_theory;
+mod statistics;
+mod uncertain;
+
+pub const MAX_DIGITS: usize = 256;
+
+const MAX_SHIFT: u64 = 1 << 20;
+
+const MAX_LENGTH: usize = 1 << 20;
+
+/// The largest denominator of a fraction suggested alongside an inexact decimal.
+const MAX_HINT_DENOMINATOR: u32 = 1000;
 
 #[derive(Debug, Default)]
 pub struct Evaluator {
     names: FxHashMap<&'static str, Value>,
+    constants: FxHashSet<&'static str>,
     options: ToSciOptions,
+    warnings: Vec<Warning>,
+    hide_warnings: bool,
+    width: Option<IntegerWidth>,
+    /// A fixed time for `now` and `today`, instead of the system clock.
+    clock: Option<DateTime>,
+    /// Exchange rates for converting between currencies.
+    rates: Option<Rates>,
+    /// Text written by commands like `help`, which is taken along with the warnings.
+    output: String,
+    /// Whether the text is written without colours, for programs that read it.
+    plain: bool,
+    /// The next unused source of error for a `+-` measurement.
+    next_source: Cell<u64>,
 }
 
 impl Evaluator {
     pub fn evaluate_statement(
         &mut self,
         stmt: Statement,
-    ) -> Result<ControlFlow<(), Option<Value>>, Error> {
+    ) -> Result<ControlFlow<(), Option<Output>>, Error> {
         match stmt {
-            Statement::Expression(expr) => self
-                .evaluate_expression(expr.0)
-                .map(Some)
-                .map(ControlFlow::Continue),
+            Statement::Expression { expr, target } => Ok(ControlFlow::Continue(Some(
+                self.evaluate_output(expr, target)?,
+            ))),
             Statement::Assign { name, value } => {
                 let value = self.evaluate_expression(value.0)?;
-                self.names.insert(name.0.resolve(), value);
+                let resolved = name.0.resolve();
+
+                if self.constants.remove(resolved) {
+                    self.warn(Warning::ShadowedConstant {
+                        name: resolved,
+                        span: name.1,
+                    });
+                } else if !self.names.contains_key(resolved) && units().any(|unit| unit == resolved)
+                {
+                    self.warn(Warning::ShadowedUnit {
+                        name: resolved,
+                        span: name.1,
+                    });
+                }
+
+                self.names.insert(resolved, value);
 
                 Ok(ControlFlow::Continue(None))
             }
@@ -59,8 +134,25 @@ impl Evaluator {
 
                 Ok(ControlFlow::Continue(None))
             }
+            Statement::SetWarnings(enabled) => {
+                self.hide_warnings = !enabled;
+
+                eprintln!("{} warnings", if enabled { "Enabled" } else { "Disabled" });
+
+                Ok(ControlFlow::Continue(None))
+            }
+            Statement::SetWidth(width) => {
+                self.width = width;
+
+                match width {
+                    Some(width) => eprintln!("Using {width} wrapping arithmetic"),
+                    None => eprintln!("Using unbounded arithmetic"),
+                }
+
+                Ok(ControlFlow::Continue(None))
+            }
             Statement::Help => {
-                print_help();
+                self.output.push_str(HELP);
 
                 Ok(ControlFlow::Continue(None))
             }
@@ -70,76 +162,461 @@ impl Evaluator {
                 Ok(ControlFlow::Break(()))
             }
             Statement::Vars => {
-                let mut vars = self.names.iter().collect::<Vec<_>>();
+                self.print_vars();
+
+                Ok(ControlFlow::Continue(None))
+            }
+            Statement::Explain(code) => {
+                let code = code.map(|code| code.resolve());
 
-                vars.sort_by_key(|(name, _)| *name);
+                let explanation = explain(code.0).ok_or(Error::UnknownErrorCode {
+                    code: code.0,
+                    span: code.1,
+                })?;
 
-                for (name, value) in vars {
-                    println!(
-                        "{} = {}{}",
-                        name.blue(),
-                        value.display(self.options),
-                        match value {
-                            Value::Number(num) => format!(" = ({num})"),
-                        }
-                        .black()
-                    );
-                }
+                self.print(explanation);
 
                 Ok(ControlFlow::Continue(None))
             }
         }
     }
 
+    fn evaluate_output(
+        &mut self,
+        expr: Spanned<Expression>,
+        target: Option<Spanned<Target>>,
+    ) -> Result<Output, Error> {
+        let value = self.evaluate_expression(expr.0)?;
+
+        let notation = target
+            .map(|target| self.evaluate_target(target))
+            .transpose()?
+            .unwrap_or_default();
+
+        // Only the default notation writes out factorizations.
+        let value = if notation == Notation::Decimal {
+            value
+        } else {
+            value.multiplied_out()
+        };
+
+        check_notation(&notation, &value, expr.1)?;
+
+        let value = match notation {
+            Notation::Currency(currency) => self.exchange(value, currency, expr.1)?,
+            Notation::Data(unit) => value.with_data_unit(unit),
+            Notation::DataRate(unit) => value.with_rate_unit(unit),
+            _ => value,
+        };
+
+        let output = Output { value, notation };
+
+        if !output.is_exact(self.options) {
+            self.warn(Warning::PrecisionLoss {
+                precision: match (&output.notation, self.options.get_size_options()) {
+                    (
+                        Notation::Decimal
+                        | Notation::Percent
+                        | Notation::Polar
+                        | Notation::Concise
+                        | Notation::Time(_)
+                        | Notation::Iso
+                        | Notation::Currency(_)
+                        | Notation::Data(_)
+                        | Notation::DataRate(_),
+                        SciSizeOptions::Precision(precision),
+                    ) => Some(precision),
+                    _ => None,
+                },
+                span: expr.1,
+            });
+        }
+
+        Ok(output)
+    }
+
+    fn print_vars(&mut self) {
+        let mut vars = self.names.iter().collect::<Vec<_>>();
+
+        vars.sort_by_key(|(name, _)| *name);
+
+        let lines = vars
+            .into_iter()
+            .map(|(name, value)| {
+                let exact = match value {
+                    Value::Number(num) | Value::Percent(num) => format!(" = ({num})"),
+                    Value::Complex(_)
+                    | Value::Interval(_)
+                    | Value::Uncertain(_)
+                    | Value::List(_)
+                    | Value::Matrix(_)
+                    | Value::Factors(_)
+                    | Value::Duration(_)
+                    | Value::Money(..)
+                    | Value::Data(..)
+                    | Value::DataRate(..) => format!(" = ({})", value.display_fraction()),
+                    Value::Bool(_) | Value::DateTime(_) => String::new(),
+                };
+
+                let display = value.display(self.options);
+
+                if self.plain {
+                    format!("{name} = {display}{exact}")
+                } else {
+                    format!("{} = {display}{}", name.blue(), exact.black())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for line in lines {
+            self.print(&line);
+        }
+    }
+
+    fn print(&mut self, line: &str) {
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
     fn evaluate_expression(&self, expr: Expression) -> Result<Value, Error> {
         match expr {
             Expression::Number(number) => Ok(Value::Number(number)),
+            Expression::DateTime(date_time) => Ok(Value::DateTime(date_time)),
+            Expression::ZonedDateTime { local, zone } => {
+                let span = zone.1;
+                let zone = Self::zone(zone)?;
+
+                // Writes the local time with an offset, which picks one of two matching moments.
+                let with_offset = |offset: i64| {
+                    DateTime {
+                        seconds: &local.seconds - Rational::from(offset),
+                        date: false,
+                    }
+                    .display_iso_with_offset(offset)
+                };
+
+                match zone.resolve(&local) {
+                    Local::Unique(date_time) if time::YEARS.contains(&date_time.civil().0) => {
+                        Ok(Value::DateTime(date_time))
+                    }
+                    Local::Unique(_) => Err(Error::DateOutOfRange(span)),
+                    Local::Ambiguous(earlier, later) => Err(Error::AmbiguousLocalTime {
+                        zone: zone.name,
+                        earlier: with_offset(earlier),
+                        later: with_offset(later),
+                        span,
+                    }),
+                    Local::Nonexistent => Err(Error::NonexistentLocalTime {
+                        zone: zone.name,
+                        span,
+                    }),
+                }
+            }
+            Expression::Bool(value) => Ok(Value::Bool(value)),
             Expression::Variable(name) => {
                 let name = name.map(|name| name.resolve());
 
+                // `now` and `today` can be shadowed like any other name.
+                let clock = match name.0 {
+                    "now" => Some(Value::DateTime(self.now())),
+                    "today" => Some(Value::DateTime(self.now().day())),
+                    _ => None,
+                };
+
                 self.names
                     .get(name.0)
                     .cloned()
-                    .ok_or(Error::UndefinedVariable {
+                    .or(clock)
+                    .ok_or_else(|| Error::UndefinedVariable {
                         name: name.0,
+                        suggestion: self.closest_name(name.0),
                         span: name.1,
                     })
             }
-            Expression::BinaryOp { op, lhs, rhs } => {
-                let lhs = self.evaluate_expression(*lhs.0)?;
-                let rhs = self.evaluate_expression(*rhs.0)?;
+            Expression::List(elements) => Ok(Value::from_list(
+                elements
+                    .into_iter()
+                    .map(|element| Ok(self.operand(element.0, element.1)?.0))
+                    .collect::<Result<_, _>>()?,
+            )),
+            Expression::Index { list, index } => {
+                let list = self.operand(*list.0, list.1)?;
+                let index = self.operand(*index.0, index.1)?;
 
-                {
-                    use BinaryOp::{Add, Div, Mul, Sub};
-                    use Value::Number;
-
-                    match (op.0, (lhs, rhs)) {
-                        (Add, (Number(lhs), Number(rhs))) => Ok(Number(lhs + rhs)),
-                        (Sub, (Number(lhs), Number(rhs))) => Ok(Number(lhs - rhs)),
-                        (Mul, (Number(lhs), Number(rhs))) => Ok(Number(lhs * rhs)),
-                        (Div, (Number(lhs), Number(rhs))) => Ok(Number(lhs / rhs)),
-                    }
+                evaluate_index(&list, &index)
+            }
+            Expression::Call { name, args } => {
+                let args = args
+                    .into_iter()
+                    .map(|arg| self.operand(arg.0, arg.1))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                Ok(self.wrap(functions::call(name.map(|name| name.resolve()), &args)?))
+            }
+            Expression::BinaryOp { op, lhs, rhs }
+                if matches!(op.0, BinaryOp::And | BinaryOp::Or) =>
+            {
+                let lhs = self.operand(*lhs.0, lhs.1)?;
+
+                // The right-hand side is only evaluated when it decides the result.
+                if boolean(op.0, &lhs)? == (op.0 == BinaryOp::Or) {
+                    return Ok(lhs.0);
                 }
+
+                let rhs = self.operand(*rhs.0, rhs.1)?;
+
+                boolean(op.0, &rhs)?;
+
+                Ok(rhs.0)
+            }
+            Expression::BinaryOp { op, lhs, rhs } => {
+                let lhs = self.operand(*lhs.0, lhs.1)?;
+                let rhs = self.operand(*rhs.0, rhs.1)?;
+
+                Ok(self.wrap(evaluate_binary_op(op, &lhs, &rhs, &self.next_source)?))
             }
             Expression::UnaryOp { op, expr } => {
-                let expr = self.evaluate_expression(*expr.0)?;
+                let expr = self.operand(*expr.0, expr.1)?;
 
-                {
-                    use UnaryOp::Neg;
-                    use Value::Number;
+                Ok(self.wrap(evaluate_unary_op(op, &expr)?))
+            }
+            Expression::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let condition = self.operand(*condition.0, condition.1)?;
 
-                    match (op.0, expr) {
-                        (Neg, Number(expr)) => Ok(Number(-expr)),
-                    }
+                // Only the branch that is taken gets evaluated, so the other may contain errors.
+                if boolean("if", &condition)? {
+                    self.evaluate_expression(*then.0)
+                } else {
+                    self.evaluate_expression(*otherwise.0)
                 }
             }
+            Expression::Error => unreachable!("expressions with errors are never evaluated"),
+        }
+    }
+
+    /// Evaluates an expression that is used by another, multiplying out any factorizations.
+    fn operand(&self, expr: Expression, span: Span) -> Result<Spanned<Value>, Error> {
+        Ok(Spanned::new(
+            self.evaluate_expression(expr)?.multiplied_out(),
+            span,
+        ))
+    }
+
+    fn wrap(&self, value: Value) -> Value {
+        let Some(width) = self.width else {
+            return value;
+        };
+
+        match value {
+            Value::Number(number) => {
+                Integer::try_from(&number).map_or(Value::Number(number), |integer| {
+                    let modulus = Integer::power_of_2(width.bits);
+                    let wrapped = integer.mod_op(&modulus);
+
+                    Value::Number(Rational::from(
+                        if width.signed && wrapped >= (&modulus >> 1u32) {
+                            wrapped - modulus
+                        } else {
+                            wrapped
+                        },
+                    ))
+                })
+            }
+            Value::List(list) => {
+                Value::List(list.into_iter().map(|value| self.wrap(value)).collect())
+            }
+            value @ (Value::Percent(_)
+            | Value::Complex(_)
+            | Value::Interval(_)
+            | Value::Uncertain(_)
+            | Value::Bool(_)
+            | Value::Matrix(_)
+            | Value::Factors(_)
+            | Value::DateTime(_)
+            | Value::Duration(_)
+            | Value::Money(..)
+            | Value::Data(..)
+            | Value::DataRate(..)) => value,
         }
     }
 
-    pub fn insert(&mut self, name: &'static str, value: f64) {
-        let value = Value::Number(Rational::try_from(value).unwrap());
+    fn evaluate_target(&self, target: Spanned<Target>) -> Result<Notation, Error> {
+        match target.0 {
+            Target::Radix(Radix::Decimal) => Ok(Notation::Decimal),
+            Target::Radix(radix) => Ok(Notation::Base(radix.to_u8())),
+            Target::Base(base) => match self.evaluate_expression(base.0)? {
+                Value::Number(number) => u8::try_from(&number)
+                    .ok()
+                    .filter(|base| (2..=36).contains(base))
+                    .map(|base| {
+                        if base == 10 {
+                            Notation::Decimal
+                        } else {
+                            Notation::Base(base)
+                        }
+                    })
+                    .ok_or(Error::InvalidBase(base.1)),
+                Value::Percent(_)
+                | Value::Complex(_)
+                | Value::Interval(_)
+                | Value::Uncertain(_)
+                | Value::Bool(_)
+                | Value::List(_)
+                | Value::Matrix(_)
+                | Value::Factors(_)
+                | Value::DateTime(_)
+                | Value::Duration(_)
+                | Value::Money(..)
+                | Value::Data(..)
+                | Value::DataRate(..) => Err(Error::InvalidBase(base.1)),
+            },
+            Target::Repeating => Ok(Notation::Base(10)),
+            Target::Fraction => Ok(Notation::Fraction),
+            Target::Mixed => Ok(Notation::Mixed),
+            Target::Percent => Ok(Notation::Percent),
+            Target::Polar => Ok(Notation::Polar),
+            Target::Concise => Ok(Notation::Concise),
+            Target::ContinuedFraction => Ok(Notation::ContinuedFraction),
+            Target::Time(unit) => Ok(Notation::Time(unit)),
+            Target::Iso => Ok(Notation::Iso),
+            Target::TimeZone(zone) => Ok(Notation::TimeZone(Self::zone(zone)?)),
+            Target::Currency(currency) => Ok(Notation::Currency(currency)),
+            Target::Data(unit) => Ok(Notation::Data(unit)),
+            Target::DataRate(unit) => Ok(Notation::DataRate(unit)),
+        }
+    }
+
+    /// The defined name, function, unit or clock closest to an undefined name.
+    fn closest_name(&self, name: &str) -> Option<&'static str> {
+        closest(
+            name,
+            self.names
+                .keys()
+                .copied()
+                .chain(functions::FUNCTIONS.iter().map(|(function, _)| *function))
+                .chain(units())
+                .chain(["now", "today"]),
+        )
+    }
 
+    fn zone(name: Spanned<Identifier>) -> Result<Zone, Error> {
+        let name = name.map(|name| name.resolve());
+
+        Zone::find(name.0).ok_or_else(|| Error::UnknownTimeZone {
+            name: name.0,
+            suggestion: closest(name.0, Zone::names()),
+            span: name.1,
+        })
+    }
+
+    /// Fixes the time used by `now` and `today`.
+    pub fn set_clock(&mut self, now: DateTime) {
+        self.clock = Some(now);
+    }
+
+    fn now(&self) -> DateTime {
+        self.clock.clone().unwrap_or_else(|| {
+            let elapsed = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default();
+
+            DateTime {
+                seconds: Rational::from(elapsed.as_secs())
+                    + Rational::from_unsigneds(elapsed.subsec_nanos(), 1_000_000_000),
+                date: false,
+            }
+        })
+    }
+
+    /// Sets the exchange rates used to convert amounts of money.
+    pub fn set_rates(&mut self, rates: Rates) {
+        self.rates = Some(rates);
+    }
+
+    /// Converts amounts of money, including those in lists, to `currency`, noting the date of the
+    /// rates whenever one is converted.
+    fn exchange(&mut self, value: Value, currency: Currency, span: Span) -> Result<Value, Error> {
+        let mut converted = false;
+        let value = self.convert(value, currency, span, &mut converted)?;
+
+        if converted {
+            self.warn(Warning::ExchangeRates {
+                date: self
+                    .rates
+                    .as_ref()
+                    .and_then(|rates| rates.date.as_ref())
+                    .map(DateTime::display),
+                span,
+            });
+        }
+
+        Ok(value)
+    }
+
+    fn convert(
+        &self,
+        value: Value,
+        to: Currency,
+        span: Span,
+        converted: &mut bool,
+    ) -> Result<Value, Error> {
+        match value {
+            Value::Money(amount, from) if from == to => Ok(Value::Money(amount, to)),
+            Value::Money(amount, from) => {
+                let missing = |currency: Currency| Error::MissingExchangeRate {
+                    currency: currency.code(),
+                    base: self.rates.as_ref().map(|rates| rates.base.code()),
+                    span,
+                };
+
+                let rates = self.rates.as_ref().ok_or_else(|| missing(to))?;
+
+                for currency in [from, to] {
+                    if !rates.contains(currency) {
+                        return Err(missing(currency));
+                    }
+                }
+
+                *converted = true;
+
+                Ok(Value::Money(rates.convert(&amount, from, to).unwrap(), to))
+            }
+            Value::List(list) => Ok(Value::List(
+                list.into_iter()
+                    .map(|value| self.convert(value, to, span, converted))
+                    .collect::<Result<_, _>>()?,
+            )),
+            value => Ok(value),
+        }
+    }
+
+    pub fn insert(&mut self, name: &'static str, value: Value) {
         self.names.insert(name, value);
+        self.constants.insert(name);
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        if !self.hide_warnings {
+            self.warnings.push(warning);
+        }
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        core::mem::take(&mut self.warnings)
+    }
+
+    /// Takes the text written by commands since the last call.
+    pub fn take_output(&mut self) -> String {
+        core::mem::take(&mut self.output)
+    }
+
+    /// Writes text without colours from now on.
+    pub const fn set_plain(&mut self) {
+        self.plain = true;
     }
 
     pub const fn options(&self) -> ToSciOptions {
@@ -147,27 +624,1533 @@ impl Evaluator {
     }
 }
 
+/// Checks that a value can be written in a notation.
+#[allow(clippy::too_many_lines)]
+fn check_notation(notation: &Notation, value: &Value, span: Span) -> Result<(), Error> {
+    match (notation, value) {
+        (Notation::Polar, Value::Interval(_)) => Err(Error::InvalidInterval {
+            operator: "to polar".into(),
+            reason: "This value is an interval",
+            span,
+        }),
+        (
+            Notation::Polar,
+            Value::Bool(_)
+            | Value::List(_)
+            | Value::Matrix(_)
+            | Value::DateTime(_)
+            | Value::Duration(_)
+            | Value::Money(..)
+            | Value::Data(..)
+            | Value::DataRate(..),
+        ) => Err(Error::WrongType {
+            operator: "to polar".into(),
+            expected: "a number",
+            found: value.type_name(),
+            span,
+        }),
+        (
+            Notation::ContinuedFraction,
+            Value::Complex(_)
+            | Value::Interval(_)
+            | Value::Uncertain(_)
+            | Value::Bool(_)
+            | Value::DateTime(_)
+            | Value::Duration(_)
+            | Value::Money(..)
+            | Value::Data(..)
+            | Value::DataRate(..),
+        ) => Err(Error::WrongType {
+            operator: "to cf".into(),
+            expected: "a real number",
+            found: value.type_name(),
+            span,
+        }),
+        (Notation::Currency(currency), value)
+            if !matches!(value, Value::Money(..) | Value::List(_)) =>
+        {
+            Err(Error::WrongType {
+                operator: format!("to {currency}"),
+                expected: "an amount of money",
+                found: value.type_name(),
+                span,
+            })
+        }
+        (Notation::Data(unit), value) if !matches!(value, Value::Data(..) | Value::List(_)) => {
+            Err(Error::WrongType {
+                operator: format!("to {unit}"),
+                expected: "an amount of data",
+                found: value.type_name(),
+                span,
+            })
+        }
+        (Notation::DataRate(unit), value)
+            if !matches!(value, Value::DataRate(..) | Value::List(_)) =>
+        {
+            Err(Error::WrongType {
+                operator: format!("to {unit}"),
+                expected: "a data rate",
+                found: value.type_name(),
+                span,
+            })
+        }
+        (Notation::Time(unit), value) if !matches!(value, Value::Duration(_) | Value::List(_)) => {
+            Err(Error::WrongType {
+                operator: format!("to {}", unit.name(false)),
+                expected: "a duration",
+                found: value.type_name(),
+                span,
+            })
+        }
+        (Notation::Iso, value)
+            if !matches!(
+                value,
+                Value::DateTime(_) | Value::Duration(_) | Value::List(_)
+            ) =>
+        {
+            Err(Error::WrongType {
+                operator: "to iso".into(),
+                expected: "a date or a duration",
+                found: value.type_name(),
+                span,
+            })
+        }
+        (Notation::TimeZone(zone), value)
+            if !matches!(value, Value::DateTime(_) | Value::List(_)) =>
+        {
+            Err(Error::WrongType {
+                operator: format!("in {}", zone.name),
+                expected: "a date",
+                found: value.type_name(),
+                span,
+            })
+        }
+        (Notation::Polar, Value::Uncertain(_)) => Err(Error::InvalidUncertain {
+            operator: "to polar".into(),
+            reason: "This value has an uncertainty",
+            span,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Notation {
+    #[default]
+    Decimal,
+    Base(u8),
+    Fraction,
+    Mixed,
+    Percent,
+    Polar,
+    Concise,
+    ContinuedFraction,
+    Time(TimeUnit),
+    Iso,
+    TimeZone(Zone),
+    Currency(Currency),
+    Data(DataUnit),
+    DataRate(RateUnit),
+}
+
+#[derive(Clone, Debug)]
+pub struct Output {
+    pub value: Value,
+    pub notation: Notation,
+}
+
+impl Output {
+    pub fn display(&self, options: ToSciOptions) -> String {
+        match &self.notation {
+            Notation::Decimal => match &self.value {
+                Value::Number(rational) if !self.value.is_exact(options) => {
+                    let display = self.value.display(options);
+
+                    match fraction_hint(rational, &display, options) {
+                        Some(hint) => format!("{display} ≈ {hint}"),
+                        None => display,
+                    }
+                }
+                value => value.display(options),
+            },
+            Notation::Base(base) => self.value.display_base(*base),
+            Notation::Fraction => self.value.display_fraction(),
+            Notation::Mixed => self.value.display_mixed(),
+            Notation::ContinuedFraction => self.value.display_continued_fraction(),
+            Notation::Time(unit) => self.value.display_in(*unit, options),
+            Notation::Iso => self.value.display_iso(options),
+            Notation::TimeZone(zone) => self.value.display_in_zone(zone, options),
+            Notation::Currency(_) | Notation::Data(_) | Notation::DataRate(_) => {
+                self.value.display(options)
+            }
+            Notation::Percent => self.value.to_percent().display(options),
+            Notation::Concise => match &self.value {
+                Value::Uncertain(uncertain) => uncertain.display(true),
+                value => value.display(options),
+            },
+            Notation::Polar => {
+                let (abs, arg) = self.value.to_polar();
+
+                format!("{} ∠ {}", abs.display(options), arg.display(options))
+            }
+        }
+    }
+
+    pub fn is_exact(&self, options: ToSciOptions) -> bool {
+        match &self.notation {
+            Notation::Decimal
+            | Notation::Concise
+            | Notation::Currency(_)
+            | Notation::Data(_)
+            | Notation::DataRate(_) => self.value.is_exact(options),
+            Notation::Base(base) => self.value.is_exact_in_base(*base),
+            Notation::Fraction
+            | Notation::Mixed
+            | Notation::ContinuedFraction
+            | Notation::Iso
+            | Notation::TimeZone(_) => true,
+            Notation::Time(unit) => self.value.in_unit(*unit).is_exact(options),
+            Notation::Percent => self.value.to_percent().is_exact(options),
+            Notation::Polar => {
+                let (abs, arg) = self.value.to_polar();
+
+                abs.is_exact(options) && arg.is_exact(options)
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Value {
     Number(Rational),
+    /// A percentage, stored as a fraction so `15%` holds `0.15`.
+    Percent(Rational),
+    /// A number with a non-zero imaginary part.
+    Complex(Complex),
+    /// Every number between two bounds, which are never equal.
+    Interval(Interval),
+    /// A measurement with a non-zero standard uncertainty.
+    Uncertain(Uncertain),
+    Bool(bool),
+    /// A list of values, which operators and most functions are applied to element by element.
+    List(Vec<Self>),
+    /// A list of at least one row, where every row is a list of the same number of numbers.
+    Matrix(Matrix),
+    /// The prime factorization of an integer, which is multiplied out before it is used.
+    Factors(Factors),
+    DateTime(DateTime),
+    /// A length of time in seconds.
+    Duration(Rational),
+    /// An amount of money in a currency.
+    Money(Rational, Currency),
+    /// An amount of information in bits, written in a unit.
+    Data(Rational, DataUnit),
+    /// A data rate in bits per second, written in a unit.
+    DataRate(Rational, RateUnit),
 }
 
 impl Value {
+    pub const I: Self = Self::Complex(Complex::I);
+
+    pub fn from_complex(complex: Complex) -> Self {
+        if complex.im == 0u32 {
+            Self::Number(complex.re)
+        } else {
+            Self::Complex(complex)
+        }
+    }
+
+    pub fn from_interval(interval: Interval) -> Self {
+        if interval.lower == interval.upper {
+            Self::Number(interval.lower)
+        } else {
+            Self::Interval(interval)
+        }
+    }
+
+    /// Turns a list of rows of numbers into a matrix.
+    pub fn from_list(list: Vec<Self>) -> Self {
+        let rows = list
+            .iter()
+            .map(|row| match row {
+                Self::List(row) => row
+                    .iter()
+                    .map(|entry| match entry {
+                        Self::Number(rational) => Some(rational.clone()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+
+        rows.and_then(Matrix::new)
+            .map_or(Self::List(list), Self::Matrix)
+    }
+
+    pub fn from_uncertain(uncertain: Uncertain) -> Self {
+        if uncertain.is_exact() {
+            Self::Number(uncertain.value)
+        } else {
+            Self::Uncertain(uncertain)
+        }
+    }
+
     pub fn display(&self, options: ToSciOptions) -> String {
+        if let Self::List(list) = self {
+            return display_list(list, |value| value.display(options));
+        }
+
+        if let Self::Uncertain(uncertain) = self {
+            return uncertain.display(false);
+        }
+
+        if let Self::Factors(factors) = self {
+            return factors.display();
+        }
+
+        if let Self::Interval(interval) = self {
+            // The bounds are rounded outwards, so the displayed interval always contains the
+            // exact one.
+            let bound = |rational: &Rational, rounding_mode| {
+                let mut options = options;
+                options.set_rounding_mode(rounding_mode);
+
+                Self::Number(rational.clone()).display(options)
+            };
+
+            return format!(
+                "{} ∈ [{}, {}]",
+                Self::Number(interval.midpoint()).display(options),
+                bound(&interval.lower, RoundingMode::Floor),
+                bound(&interval.upper, RoundingMode::Ceiling)
+            );
+        }
+
+        self.display_with(|rational| match options.get_size_options() {
+            SciSizeOptions::Complete if rational.length_after_point_in_small_base(10).is_none() => {
+                Expansion::new(rational, 10, MAX_DIGITS).display("")
+            }
+            _ => rational.to_sci_with_options(options).to_string(),
+        })
+    }
+
+    pub fn display_base(&self, base: u8) -> String {
+        let prefix = match base {
+            2 => "0b",
+            8 => "0o",
+            16 => "0x",
+            _ => "",
+        };
+
+        let digits = self
+            .display_with(|rational| Expansion::new(rational, base, MAX_DIGITS).display(prefix));
+
+        match base {
+            2 | 8 | 10 | 16 => digits,
+            _ => format!("{digits} (base {base})"),
+        }
+    }
+
+    pub fn display_fraction(&self) -> String {
+        self.display_with(ToString::to_string)
+    }
+
+    pub fn display_mixed(&self) -> String {
+        self.display_with(|rational| {
+            let sign = if *rational < 0u32 { "-" } else { "" };
+
+            let (numerator, denominator) = rational.numerator_and_denominator_ref();
+            let (whole, remainder) = numerator.div_mod(denominator);
+
+            if remainder == 0u32 {
+                format!("{sign}{whole}")
+            } else if whole == 0u32 {
+                format!("{sign}{remainder}/{denominator}")
+            } else {
+                format!("{sign}{whole} {remainder}/{denominator}")
+            }
+        })
+    }
+
+    /// Writes durations as a number of `unit`, like `68 days`.
+    pub fn display_in(&self, unit: TimeUnit, options: ToSciOptions) -> String {
+        match self {
+            Self::Duration(seconds) => {
+                let amount = seconds / unit.seconds();
+
+                format!(
+                    "{} {}",
+                    Self::Number(amount.clone()).display(options),
+                    unit.name(amount == 1u32)
+                )
+            }
+            Self::List(list) => display_list(list, |value| value.display_in(unit, options)),
+            value => value.display(options),
+        }
+    }
+
+    /// Writes dates and durations in ISO 8601, like `2026-10-17T09:30:00Z` and `PT1H30M`.
+    pub fn display_iso(&self, options: ToSciOptions) -> String {
+        match self {
+            Self::DateTime(date_time) => date_time.display_iso(),
+            Self::Duration(seconds) => time::display_iso_duration(seconds, |seconds| {
+                Self::Number(seconds.clone()).display(options)
+            }),
+            Self::List(list) => display_list(list, |value| value.display_iso(options)),
+            value => value.display(options),
+        }
+    }
+
+    /// Writes dates in the local time of a zone, like `2026-10-17 05:30:00 EDT (UTC-04:00)`.
+    pub fn display_in_zone(&self, zone: &Zone, options: ToSciOptions) -> String {
         match self {
-            Self::Number(rational) => rational.to_sci_with_options(options).to_string(),
+            Self::DateTime(date_time) => zone.display(date_time),
+            Self::List(list) => display_list(list, |value| value.display_in_zone(zone, options)),
+            value => value.display(options),
         }
     }
+
+    /// Replaces durations, including those in lists, with their number of `unit`.
+    fn in_unit(&self, unit: TimeUnit) -> Self {
+        match self {
+            Self::Duration(seconds) => Self::Number(seconds / unit.seconds()),
+            Self::List(list) => Self::List(list.iter().map(|value| value.in_unit(unit)).collect()),
+            value => value.clone(),
+        }
+    }
+
+    /// Writes amounts of data, including those in lists, in `unit`.
+    fn with_data_unit(self, unit: DataUnit) -> Self {
+        match self {
+            Self::Data(bits, _) => Self::Data(bits, unit),
+            Self::List(list) => Self::List(
+                list.into_iter()
+                    .map(|value| value.with_data_unit(unit))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+
+    /// Writes data rates, including those in lists, in `unit`.
+    fn with_rate_unit(self, unit: RateUnit) -> Self {
+        match self {
+            Self::DataRate(rate, _) => Self::DataRate(rate, unit),
+            Self::List(list) => Self::List(
+                list.into_iter()
+                    .map(|value| value.with_rate_unit(unit))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+
+    /// Writes every part as a continued fraction, like `[3; 7, 15, 1]`.
+    pub fn display_continued_fraction(&self) -> String {
+        self.display_with(|rational| {
+            let (floor, terms) = rational.clone().continued_fraction();
+            let terms = terms.map(|term| term.to_string()).collect::<Vec<_>>();
+
+            if terms.is_empty() {
+                format!("[{floor}]")
+            } else {
+                format!("[{floor}; {}]", terms.join(", "))
+            }
+        })
+    }
+
+    pub fn is_exact(&self, mut options: ToSciOptions) -> bool {
+        if let Self::List(list) = self {
+            return list.iter().all(|value| value.is_exact(options));
+        }
+
+        // Uncertain values are rounded to the digits their uncertainty makes significant, and
+        // factorizations are always written out in full.
+        if let Self::Uncertain(_) | Self::Factors(_) = self {
+            return true;
+        }
+
+        if options.get_size_options() == SciSizeOptions::Complete {
+            return self.is_exact_in_base(10);
+        }
+
+        options.set_rounding_mode(RoundingMode::Exact);
+
+        self.all_parts(|rational| rational.fmt_sci_valid(options))
+    }
+
+    pub fn is_exact_in_base(&self, base: u8) -> bool {
+        self.all_parts(|rational| !Expansion::new(rational, base, MAX_DIGITS).is_truncated())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn to_json(&self, options: ToSciOptions) -> Json {
+        let mut fields = match self {
+            Self::Number(rational) => {
+                let mut fields = vec![("type", Json::string("number"))];
+                fields.extend(rational_json(rational));
+                fields
+            }
+            Self::Percent(rational) => {
+                let mut fields = vec![("type", Json::string("percent"))];
+                fields.extend(rational_json(rational));
+                fields
+            }
+            Self::Complex(complex) => vec![
+                ("type", Json::string("complex")),
+                ("real", Json::Object(rational_json(&complex.re))),
+                ("imaginary", Json::Object(rational_json(&complex.im))),
+            ],
+            Self::Interval(interval) => vec![
+                ("type", Json::string("interval")),
+                ("lower", Json::Object(rational_json(&interval.lower))),
+                ("upper", Json::Object(rational_json(&interval.upper))),
+            ],
+            Self::Uncertain(uncertain) => vec![
+                ("type", Json::string("uncertain")),
+                ("value", Json::Object(rational_json(&uncertain.value))),
+                (
+                    "variance",
+                    Json::Object(rational_json(&uncertain.variance())),
+                ),
+            ],
+            Self::Bool(value) => vec![
+                ("type", Json::string("bool")),
+                ("value", Json::Bool(*value)),
+            ],
+            Self::Matrix(matrix) => vec![
+                ("type", Json::string("matrix")),
+                (
+                    "rows",
+                    Json::Array(
+                        (0..matrix.shape().0)
+                            .map(|row| {
+                                Json::Array(
+                                    matrix
+                                        .row(row)
+                                        .iter()
+                                        .map(|entry| Json::Object(rational_json(entry)))
+                                        .collect(),
+                                )
+                            })
+                            .collect(),
+                    ),
+                ),
+            ],
+            Self::Factors(factors) => vec![
+                ("type", Json::string("factors")),
+                ("negative", Json::Bool(factors.negative)),
+                (
+                    "factors",
+                    Json::Array(
+                        factors
+                            .factors
+                            .iter()
+                            .map(|(prime, exponent)| {
+                                Json::Object(vec![
+                                    ("prime", Json::String(prime.to_string())),
+                                    ("exponent", Json::String(exponent.to_string())),
+                                ])
+                            })
+                            .collect(),
+                    ),
+                ),
+            ],
+            Self::DateTime(date_time) => vec![
+                ("type", Json::string("datetime")),
+                ("iso", Json::String(date_time.display_iso())),
+                ("seconds", Json::Object(rational_json(&date_time.seconds))),
+            ],
+            Self::Duration(seconds) => vec![
+                ("type", Json::string("duration")),
+                ("seconds", Json::Object(rational_json(seconds))),
+            ],
+            Self::Money(amount, currency) => vec![
+                ("type", Json::string("money")),
+                ("currency", Json::string(currency.code())),
+                ("amount", Json::Object(rational_json(amount))),
+            ],
+            Self::Data(bits, unit) => vec![
+                ("type", Json::string("data")),
+                ("unit", Json::String(unit.to_string())),
+                ("bits", Json::Object(rational_json(bits))),
+            ],
+            Self::DataRate(rate, unit) => vec![
+                ("type", Json::string("data_rate")),
+                ("unit", Json::String(unit.to_string())),
+                ("bits_per_second", Json::Object(rational_json(rate))),
+            ],
+            Self::List(list) => vec![
+                ("type", Json::string("list")),
+                (
+                    "elements",
+                    Json::Array(list.iter().map(|value| value.to_json(options)).collect()),
+                ),
+            ],
+        };
+
+        fields.push(("decimal", Json::String(self.display(options))));
+
+        Json::Object(fields)
+    }
+
+    pub fn to_complex(&self) -> Complex {
+        match self {
+            Self::Number(rational) | Self::Percent(rational) => Complex::real(rational.clone()),
+            Self::Complex(complex) => complex.clone(),
+            Self::Interval(interval) => Complex::real(interval.midpoint()),
+            Self::Uncertain(uncertain) => Complex::real(uncertain.value.clone()),
+            Self::Bool(_) => unreachable!("booleans are rejected before they are used as numbers"),
+            Self::List(_) => unreachable!("lists are broadcast before they are used as numbers"),
+            Self::Matrix(_) => unreachable!("matrices are handled before they are used as numbers"),
+            Self::Factors(_) => {
+                unreachable!("factorizations are multiplied out before they are used")
+            }
+            Self::DateTime(_) | Self::Duration(_) => {
+                unreachable!("times are handled before they are used as numbers")
+            }
+            Self::Money(..) | Self::Data(..) | Self::DataRate(..) => {
+                unreachable!("amounts are handled before they are used as numbers")
+            }
+        }
+    }
+
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "a number",
+            Self::Percent(_) => "a percentage",
+            Self::Complex(_) => "a complex number",
+            Self::Interval(_) => "an interval",
+            Self::Uncertain(_) => "a measurement",
+            Self::Bool(_) => "a boolean",
+            Self::List(_) => "a list",
+            Self::Matrix(_) => "a matrix",
+            Self::Factors(_) => "a factorization",
+            Self::DateTime(_) => "a date",
+            Self::Duration(_) => "a duration",
+            Self::Money(..) => "an amount of money",
+            Self::Data(..) => "an amount of data",
+            Self::DataRate(..) => "a data rate",
+        }
+    }
+
+    /// Replaces factorizations, including those in lists, with the numbers they multiply out to.
+    fn multiplied_out(self) -> Self {
+        match self {
+            Self::Factors(factors) => Self::Number(factors.value()),
+            Self::List(list) => Self::List(list.into_iter().map(Self::multiplied_out).collect()),
+            value => value,
+        }
+    }
+
+    fn to_percent(&self) -> Self {
+        match self {
+            Self::Number(rational) => Self::Percent(rational.clone()),
+            Self::List(list) => Self::List(list.iter().map(Self::to_percent).collect()),
+            value => value.clone(),
+        }
+    }
+
+    /// The magnitude and the angle in radians.
+    fn to_polar(&self) -> (Self, Self) {
+        let complex = self.to_complex();
+
+        (Self::Number(complex.abs()), Self::Number(complex.arg()))
+    }
+
+    const fn is_zero(&self) -> bool {
+        matches!(
+            self,
+            Self::Number(Rational::ZERO)
+                | Self::Percent(Rational::ZERO)
+                | Self::Duration(Rational::ZERO)
+                | Self::Money(Rational::ZERO, _)
+                | Self::Data(Rational::ZERO, _)
+                | Self::DataRate(Rational::ZERO, _)
+        )
+    }
+
+    /// Formats every part of the value with `f`, so percentages are scaled and suffixed with `%`,
+    /// and complex numbers are written as `a + bi`.
+    fn display_with(&self, f: impl Fn(&Rational) -> String) -> String {
+        match self {
+            Self::Number(rational) => f(rational),
+            Self::Percent(rational) => format!("{}%", f(&(rational * Rational::from(100u32)))),
+            Self::Complex(complex) => complex.display_with(f),
+            Self::Interval(interval) => format!(
+                "{} ∈ [{}, {}]",
+                f(&interval.midpoint()),
+                f(&interval.lower),
+                f(&interval.upper)
+            ),
+            Self::Uncertain(uncertain) => {
+                format!("{} ± {}", f(&uncertain.value), f(&uncertain.uncertainty()))
+            }
+            Self::Bool(value) => value.to_string(),
+            Self::List(list) => display_list(list, |value| {
+                value.display_with(&f as &dyn Fn(&Rational) -> String)
+            }),
+            Self::Matrix(matrix) => display_list(&rows(matrix), |value| {
+                value.display_with(&f as &dyn Fn(&Rational) -> String)
+            }),
+            Self::Factors(factors) => f(&factors.value()),
+            Self::DateTime(date_time) => date_time.display(),
+            Self::Duration(seconds) => time::display_duration(seconds, f),
+            Self::Money(amount, currency) => format!("{} {currency}", f(amount)),
+            Self::Data(bits, unit) => format!("{} {unit}", f(&(bits / unit.bits()))),
+            Self::DataRate(rate, unit) => {
+                format!("{} {unit}", f(&(rate / unit.bits_per_second())))
+            }
+        }
+    }
+
+    fn all_parts(&self, f: impl Fn(&Rational) -> bool) -> bool {
+        match self {
+            Self::Number(rational) => f(rational),
+            Self::Percent(rational) => f(&(rational * Rational::from(100u32))),
+            Self::Complex(complex) => f(&complex.re) && f(&complex.im),
+            Self::Interval(interval) => f(&interval.lower) && f(&interval.upper),
+            Self::Uncertain(uncertain) => f(&uncertain.value) && f(&uncertain.uncertainty()),
+            Self::Bool(_) | Self::DateTime(_) => true,
+            Self::List(list) => list
+                .iter()
+                .all(|value| value.all_parts(&f as &dyn Fn(&Rational) -> bool)),
+            Self::Matrix(matrix) => matrix.entries().iter().all(f),
+            Self::Factors(factors) => f(&factors.value()),
+            Self::Duration(seconds) | Self::Money(seconds, _) => f(seconds),
+            Self::Data(bits, unit) => f(&(bits / unit.bits())),
+            Self::DataRate(rate, unit) => f(&(rate / unit.bits_per_second())),
+        }
+    }
+}
+
+/// The simplest fraction with a small denominator that is displayed as `display`, found among the
+/// convergents of the continued fraction, so `0.3333333333333333` gets the hint `1/3`.
+fn fraction_hint(rational: &Rational, display: &str, options: ToSciOptions) -> Option<Rational> {
+    rational
+        .clone()
+        .convergents()
+        .take_while(|convergent| *convergent.denominator_ref() <= MAX_HINT_DENOMINATOR)
+        .filter(|convergent| *convergent.denominator_ref() != 1u32)
+        .find(|convergent| Value::Number(convergent.clone()).display(options) == display)
+}
+
+fn display_list(list: &[Value], f: impl Fn(&Value) -> String) -> String {
+    format!("[{}]", list.iter().map(f).collect::<Vec<_>>().join(", "))
+}
+
+/// The rows of a matrix as lists.
+fn rows(matrix: &Matrix) -> Vec<Value> {
+    (0..matrix.shape().0)
+        .map(|row| Value::List(matrix.row(row).iter().cloned().map(Value::Number).collect()))
+        .collect()
+}
+
+fn rational_json(rational: &Rational) -> Vec<(&'static str, Json)> {
+    let sign = if *rational < 0u32 { "-" } else { "" };
+
+    vec![
+        (
+            "numerator",
+            Json::String(format!("{sign}{}", rational.numerator_ref())),
+        ),
+        (
+            "denominator",
+            Json::String(rational.denominator_ref().to_string()),
+        ),
+    ]
+}
+
+fn evaluate_binary_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+    next_source: &Cell<u64>,
+) -> Result<Value, Error> {
+    use BinaryOp::{
+        Add, And, BitAnd, BitOr, BitXor, Div, Eq, Ge, Gt, IntDiv, Le, Lt, Mod, Mul, Ne, Or,
+        PercentOf, PlusMinus, Range, Shl, Shr, Sub, Uncertainty,
+    };
+    use Value::{
+        Bool, Complex, Data, DataRate, DateTime, Duration, Interval, List, Matrix, Money, Number,
+        Percent, Uncertain,
+    };
+
+    let reals = || Ok::<_, Error>((real(op.0, lhs)?, real(op.0, rhs)?));
+
+    if matches!(op.0, Div | Mod | IntDiv | PercentOf) && rhs.0.is_zero() {
+        return Err(Error::DivisionByZero(rhs.1));
+    }
+
+    Ok(match (op.0, (&lhs.0, &rhs.0)) {
+        (Range, _) => evaluate_range(op, lhs, rhs)?,
+        (_, (Matrix(_), _) | (_, Matrix(_))) => evaluate_matrix_op(op, lhs, rhs)?,
+        (_, (List(_), _) | (_, List(_))) => evaluate_list_op(op, lhs, rhs, next_source)?,
+        (_, (Data(..) | DataRate(..), _) | (_, Data(..) | DataRate(..))) => {
+            evaluate_data_op(op, lhs, rhs)?
+        }
+        (_, (DateTime(_) | Duration(_), _) | (_, DateTime(_) | Duration(_))) => {
+            evaluate_time_op(op, lhs, rhs)?
+        }
+        (_, (Money(..), _) | (_, Money(..))) => evaluate_money_op(op, lhs, rhs)?,
+        (Eq | Ne | Lt | Le | Gt | Ge | And | Or, _) => evaluate_logical_op(op, lhs, rhs)?,
+        (_, (Bool(_), _)) => return Err(wrong_type(op.0, lhs, "a number")),
+        (_, (_, Bool(_))) => return Err(wrong_type(op.0, rhs, "a number")),
+        // Adding or subtracting a percentage applies it to the left-hand side, like on a
+        // desk calculator.
+        (Add, (Number(lhs), Percent(rhs))) => Number(lhs * (Rational::from(1u32) + rhs)),
+        (Sub, (Number(lhs), Percent(rhs))) => Number(lhs * (Rational::from(1u32) - rhs)),
+        (Add, (Percent(lhs), Percent(rhs))) => Percent(lhs + rhs),
+        (Sub, (Percent(lhs), Percent(rhs))) => Percent(lhs - rhs),
+        // A percentage of a number is a number in either order, and a percentage of a
+        // percentage is still a percentage.
+        (Mul, (Percent(lhs), Percent(rhs))) => Percent(lhs * rhs),
+        (Div, (Percent(lhs), Number(rhs))) => Percent(lhs / rhs),
+        (PlusMinus, _) | (Add | Sub | Mul | Div, (Interval(_), _) | (_, Interval(_))) => {
+            evaluate_interval_op(op, lhs, rhs)?
+        }
+        (Uncertainty, _) | (Add | Sub | Mul | Div, (Uncertain(_), _) | (_, Uncertain(_))) => {
+            evaluate_uncertain_op(op, lhs, rhs, next_source)?
+        }
+        (Add | Sub | Mul | Div, (Complex(_), _) | (_, Complex(_))) => {
+            let (lhs, rhs) = (lhs.0.to_complex(), rhs.0.to_complex());
+
+            Value::from_complex(match op.0 {
+                Add => lhs + rhs,
+                Sub => lhs - rhs,
+                Mul => lhs * rhs,
+                _ => lhs / rhs,
+            })
+        }
+        (Add, _) => {
+            let (lhs, rhs) = reals()?;
+            Number(lhs + rhs)
+        }
+        (Sub, _) => {
+            let (lhs, rhs) = reals()?;
+            Number(lhs - rhs)
+        }
+        (Mul, _) => {
+            let (lhs, rhs) = reals()?;
+            Number(lhs * rhs)
+        }
+        (Div, _) => {
+            let (lhs, rhs) = reals()?;
+            Number(lhs / rhs)
+        }
+        (PercentOf, _) => {
+            let (lhs, rhs) = reals()?;
+            Percent(lhs / rhs)
+        }
+        (Mod | IntDiv | BitAnd | BitOr | BitXor | Shl | Shr, _) => {
+            evaluate_integer_op(op, lhs, rhs)?
+        }
+    })
+}
+
+fn evaluate_unary_op(op: Spanned<UnaryOp>, expr: &Spanned<Value>) -> Result<Value, Error> {
+    use UnaryOp::{Currency, Data, DataRate, Factorial, LogicalNot, Neg, Not, Percent, Time};
+
+    Ok(match (op.0, &expr.0) {
+        (_, Value::List(list)) => Value::List(
+            list.iter()
+                .map(|value| evaluate_unary_op(op, &Spanned::new(value.clone(), expr.1)))
+                .collect::<Result<_, _>>()?,
+        ),
+        (Neg, Value::Number(expr)) => Value::Number(-expr),
+        (Neg, Value::Percent(expr)) => Value::Percent(-expr),
+        (Neg, Value::Complex(expr)) => Value::Complex(-expr.clone()),
+        (Neg, Value::Interval(expr)) => Value::Interval(-expr.clone()),
+        (Neg, Value::Uncertain(expr)) => Value::Uncertain(-expr.clone()),
+        (Neg, Value::Matrix(expr)) => Value::Matrix(expr.map(|entry| -entry)),
+        (Neg, Value::Duration(expr)) => Value::Duration(-expr),
+        (Neg, Value::Money(amount, currency)) => Value::Money(-amount, *currency),
+        (Neg, Value::Data(bits, unit)) => Value::Data(-bits, *unit),
+        (Neg, Value::DataRate(rate, unit)) => Value::DataRate(-rate, *unit),
+        (Neg, Value::Bool(_) | Value::DateTime(_)) => {
+            return Err(wrong_type(op.0, expr, "a number"))
+        }
+        (Neg, Value::Factors(_)) => {
+            unreachable!("factorizations are multiplied out before they are used")
+        }
+        (LogicalNot, _) => Value::Bool(!boolean(op.0, expr)?),
+        (Not, _) => Value::Number(Rational::from(!integer(op.0, expr)?)),
+        (Percent, _) => Value::Percent(real(op.0, expr)? / Rational::from(100u32)),
+        (Factorial, _) => number_theory::factorial(expr)?,
+        (Time(unit), _) => Value::Duration(real(op.0, expr)? * unit.seconds()),
+        (Currency(currency), _) => Value::Money(real(op.0, expr)?.clone(), currency),
+        (Data(unit), _) => Value::Data(real(op.0, expr)? * unit.bits(), unit),
+        (DataRate(unit), _) => Value::DataRate(real(op.0, expr)? * unit.bits_per_second(), unit),
+    })
+}
+
+// Matrices are multiplied as matrices rather than element by element, and can be scaled by a
+// number.
+fn evaluate_matrix_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{Add, Div, Mul, Sub};
+
+    let mismatch = |lhs_shape, rhs_shape| Error::ShapeMismatch {
+        operator: op.0.to_string(),
+        lhs: lhs_shape,
+        rhs: rhs_shape,
+        span: lhs.1.union(rhs.1),
+    };
+
+    Ok(Value::Matrix(match (op.0, &lhs.0, &rhs.0) {
+        (Mul, Value::Matrix(a), Value::Matrix(b)) if a.shape().1 == b.shape().0 => a.mul(b),
+        (Add | Sub, Value::Matrix(a), Value::Matrix(b)) if a.shape() == b.shape() => {
+            if op.0 == Add {
+                a.zip_with(b, |a, b| a + b)
+            } else {
+                a.zip_with(b, |a, b| a - b)
+            }
+        }
+        (Mul | Add | Sub, Value::Matrix(a), Value::Matrix(b)) => {
+            return Err(mismatch(describe(a), describe(b)))
+        }
+        (Mul, Value::Matrix(a), Value::List(_)) => {
+            let vector = vector(op.0, rhs)?;
+
+            if vector.len() != a.shape().1 {
+                return Err(mismatch(describe(a), describe_list(vector.len())));
+            }
+
+            return Ok(Value::List(
+                a.mul_vector(&vector)
+                    .into_iter()
+                    .map(Value::Number)
+                    .collect(),
+            ));
+        }
+        (Mul, Value::Matrix(a), _) => {
+            let scale = real(op.0, rhs)?;
+            a.map(|entry| entry * scale)
+        }
+        (Div, Value::Matrix(a), _) => {
+            let scale = real(op.0, rhs)?;
+            a.map(|entry| entry / scale)
+        }
+        (Mul, _, Value::Matrix(b)) => {
+            let scale = real(op.0, lhs)?;
+            b.map(|entry| scale * entry)
+        }
+        (_, Value::Matrix(_), _) => return Err(wrong_type(op.0, lhs, "a number")),
+        _ => return Err(wrong_type(op.0, rhs, "a number")),
+    }))
+}
+
+fn describe(matrix: &Matrix) -> String {
+    let (rows, columns) = matrix.shape();
+
+    format!("a {rows}×{columns} matrix")
+}
+
+fn describe_list(length: usize) -> String {
+    format!(
+        "a list of {length} element{}",
+        if length == 1 { "" } else { "s" }
+    )
 }
 
-fn print_help() {
-    println!("Syntax:");
-    println!("  <expr> - Evaluate an expression and print the result");
-    println!("  <var> = <expr> - Assign a value to a variable");
-    println!();
-    println!("Commands:");
-    println!("  precision <p> - Set the precision of numbers to <p>");
-    println!("  fullprecision - Use full precision for numbers");
-    println!("  help - Print this help message");
-    println!("  exit - Exit the program");
+// A single value is paired with every element of a list, and two lists are paired element by
+// element.
+fn evaluate_list_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+    next_source: &Cell<u64>,
+) -> Result<Value, Error> {
+    let apply = |left: &Value, right: &Value| {
+        evaluate_binary_op(
+            op,
+            &Spanned::new(left.clone(), lhs.1),
+            &Spanned::new(right.clone(), rhs.1),
+            next_source,
+        )
+    };
+
+    Ok(Value::List(match (&lhs.0, &rhs.0) {
+        (Value::List(left), Value::List(right)) if left.len() != right.len() => {
+            return Err(Error::LengthMismatch {
+                operator: op.0.to_string(),
+                lhs: left.len(),
+                rhs: right.len(),
+                span: lhs.1.union(rhs.1),
+            })
+        }
+        (Value::List(left), Value::List(right)) => left
+            .iter()
+            .zip(right)
+            .map(|(left, right)| apply(left, right))
+            .collect::<Result<_, _>>()?,
+        (Value::List(left), right) => left
+            .iter()
+            .map(|left| apply(left, right))
+            .collect::<Result<_, _>>()?,
+        (left, Value::List(right)) => right
+            .iter()
+            .map(|right| apply(left, right))
+            .collect::<Result<_, _>>()?,
+        _ => unreachable!("one of the operands is a list"),
+    }))
+}
+
+/// Every integer from `lhs` to `rhs`, including both.
+fn evaluate_range(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    let (start, end) = (integer(op.0, lhs)?, integer(op.0, rhs)?);
+
+    let length = if end < start {
+        0
+    } else {
+        usize::try_from(&(&end - &start))
+            .ok()
+            .filter(|length| *length < MAX_LENGTH)
+            .ok_or_else(|| Error::InvalidList {
+                operator: op.0.to_string(),
+                reason: "This range has too many elements",
+                span: lhs.1.union(rhs.1),
+            })?
+            + 1
+    };
+
+    Ok(Value::List(
+        (0..length)
+            .map(|offset| Value::Number(Rational::from(&start + Integer::from(offset))))
+            .collect(),
+    ))
+}
+
+// Negative indices count back from the end of the list.
+fn evaluate_index(list: &Spanned<Value>, index: &Spanned<Value>) -> Result<Value, Error> {
+    let elements = self::list("[]", list)?;
+
+    let position = integer("[]", index)?;
+    let length = elements.len();
+
+    let offset = if position < 0u32 {
+        &position + Integer::from(length)
+    } else {
+        position.clone()
+    };
+
+    usize::try_from(&offset)
+        .ok()
+        .and_then(|offset| elements.get(offset))
+        .cloned()
+        .ok_or_else(|| Error::IndexOutOfRange {
+            index: position.to_string(),
+            length,
+            span: index.1,
+        })
+}
+
+// Amounts of money can be added to and compared with amounts in the same currency, and scaled by
+// numbers and percentages, but are never converted on the way.
+fn evaluate_money_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{Add, Div, Eq, Ge, Gt, Le, Lt, Mul, Ne, Sub};
+    use Value::{Money, Percent};
+
+    Ok(match (op.0, &lhs.0, &rhs.0) {
+        (Add | Sub | Div | Eq | Ne | Lt | Le | Gt | Ge, Money(_, a), Money(_, b)) if a != b => {
+            return Err(Error::DimensionMismatch {
+                operator: op.0.to_string(),
+                lhs: a.to_string(),
+                rhs: b.to_string(),
+                span: lhs.1.union(rhs.1),
+            })
+        }
+        (Add, Money(lhs, currency), Money(rhs, _)) => Money(lhs + rhs, *currency),
+        (Sub, Money(lhs, currency), Money(rhs, _)) => Money(lhs - rhs, *currency),
+        (Div, Money(lhs, _), Money(rhs, _)) => Value::Number(lhs / rhs),
+        (Eq | Ne | Lt | Le | Gt | Ge, Money(lhs, _), Money(rhs, _)) => {
+            Value::Bool(compare(op.0, lhs.cmp(rhs)))
+        }
+        // Like with numbers, adding or subtracting a percentage applies it to the amount.
+        (Add, Money(amount, currency), Percent(rhs)) => {
+            Money(amount * (Rational::from(1u32) + rhs), *currency)
+        }
+        (Sub, Money(amount, currency), Percent(rhs)) => {
+            Money(amount * (Rational::from(1u32) - rhs), *currency)
+        }
+        (Mul, Money(amount, currency), _) => Money(amount * real(op.0, rhs)?, *currency),
+        (Mul, _, Money(amount, currency)) => Money(real(op.0, lhs)? * amount, *currency),
+        (Div, Money(amount, currency), _) => Money(amount / real(op.0, rhs)?, *currency),
+        (Eq | Ne | Lt | Le | Gt | Ge, _, _) => {
+            return Err(wrong_type(op.0, rhs, lhs.0.type_name()))
+        }
+        (Add | Sub, Money(..), _) => return Err(wrong_type(op.0, rhs, "an amount of money")),
+        (Add | Sub, _, _) => return Err(wrong_type(op.0, lhs, "an amount of money")),
+        (_, Money(..), _) => return Err(wrong_type(op.0, lhs, "a number")),
+        _ => return Err(wrong_type(op.0, rhs, "a number")),
+    })
+}
+
+// Amounts of data and data rates can be added to and compared with their own kind in any unit,
+// and combine with durations, so dividing an amount by a rate gives the time to transfer it.
+fn evaluate_data_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{Add, Div, Eq, Ge, Gt, Le, Lt, Mul, Ne, Sub};
+    use Value::{Data, DataRate, Duration};
+
+    Ok(match (op.0, &lhs.0, &rhs.0) {
+        (Add, Data(lhs, unit), Data(rhs, _)) => Data(lhs + rhs, *unit),
+        (Sub, Data(lhs, unit), Data(rhs, _)) => Data(lhs - rhs, *unit),
+        (Add, DataRate(lhs, unit), DataRate(rhs, _)) => DataRate(lhs + rhs, *unit),
+        (Sub, DataRate(lhs, unit), DataRate(rhs, _)) => DataRate(lhs - rhs, *unit),
+        (Div, Data(lhs, _), Data(rhs, _)) | (Div, DataRate(lhs, _), DataRate(rhs, _)) => {
+            Value::Number(lhs / rhs)
+        }
+        (Eq | Ne | Lt | Le | Gt | Ge, Data(lhs, _), Data(rhs, _))
+        | (Eq | Ne | Lt | Le | Gt | Ge, DataRate(lhs, _), DataRate(rhs, _)) => {
+            Value::Bool(compare(op.0, lhs.cmp(rhs)))
+        }
+        (Div, Data(bits, _), DataRate(rate, _)) => Duration(bits / rate),
+        (Div, Data(bits, unit), Duration(seconds)) => {
+            DataRate(bits / seconds, RateUnit::per_second(*unit))
+        }
+        (Mul, DataRate(rate, unit), Duration(seconds))
+        | (Mul, Duration(seconds), DataRate(rate, unit)) => Data(rate * seconds, unit.data),
+        (Mul, Data(bits, unit), _) => Data(bits * real(op.0, rhs)?, *unit),
+        (Mul, _, Data(bits, unit)) => Data(real(op.0, lhs)? * bits, *unit),
+        (Div, Data(bits, unit), _) => Data(bits / real(op.0, rhs)?, *unit),
+        (Mul, DataRate(rate, unit), _) => DataRate(rate * real(op.0, rhs)?, *unit),
+        (Mul, _, DataRate(rate, unit)) => DataRate(real(op.0, lhs)? * rate, *unit),
+        (Div, DataRate(rate, unit), _) => DataRate(rate / real(op.0, rhs)?, *unit),
+        (Eq | Ne | Lt | Le | Gt | Ge | Add | Sub, Data(..) | DataRate(..), _) => {
+            return Err(wrong_type(op.0, rhs, lhs.0.type_name()))
+        }
+        (Eq | Ne | Lt | Le | Gt | Ge | Add | Sub, _, _) => {
+            return Err(wrong_type(op.0, lhs, rhs.0.type_name()))
+        }
+        (_, Data(..) | DataRate(..), _) => return Err(wrong_type(op.0, rhs, "a number")),
+        _ => return Err(wrong_type(op.0, lhs, "a number")),
+    })
 }
+
+/// Whether an ordering satisfies a comparison operator.
+const fn compare(op: BinaryOp, ordering: core::cmp::Ordering) -> bool {
+    match op {
+        BinaryOp::Eq => ordering.is_eq(),
+        BinaryOp::Ne => ordering.is_ne(),
+        BinaryOp::Lt => ordering.is_lt(),
+        BinaryOp::Le => ordering.is_le(),
+        BinaryOp::Gt => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }
+}
+
+// Dates can be moved by durations and subtracted from each other, and durations can be added
+// together and scaled by numbers.
+fn evaluate_time_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{Add, Div, Eq, Ge, Gt, Le, Lt, Mul, Ne, Sub};
+    use Value::{DateTime, Duration};
+
+    let shifted = |date_time: &time::DateTime, seconds: &Rational| {
+        date_time
+            .shifted(seconds)
+            .map(DateTime)
+            .ok_or_else(|| Error::DateOutOfRange(lhs.1.union(rhs.1)))
+    };
+
+    Ok(match (op.0, &lhs.0, &rhs.0) {
+        (Add, DateTime(date_time), Duration(seconds))
+        | (Add, Duration(seconds), DateTime(date_time)) => shifted(date_time, seconds)?,
+        (Sub, DateTime(date_time), Duration(seconds)) => shifted(date_time, &-seconds)?,
+        (Sub, DateTime(lhs), DateTime(rhs)) => Duration(&lhs.seconds - &rhs.seconds),
+        (Add, Duration(lhs), Duration(rhs)) => Duration(lhs + rhs),
+        (Sub, Duration(lhs), Duration(rhs)) => Duration(lhs - rhs),
+        (Div, Duration(lhs), Duration(rhs)) => Value::Number(lhs / rhs),
+        (Mul, Duration(seconds), _) => Duration(seconds * real(op.0, rhs)?),
+        (Mul, _, Duration(seconds)) => Duration(real(op.0, lhs)? * seconds),
+        (Div, Duration(seconds), _) => Duration(seconds / real(op.0, rhs)?),
+        (Eq | Ne | Lt | Le | Gt | Ge, DateTime(_), DateTime(_))
+        | (Eq | Ne | Lt | Le | Gt | Ge, Duration(_), Duration(_)) => {
+            let seconds = |value: &Value| match value {
+                DateTime(date_time) => date_time.seconds.clone(),
+                Duration(seconds) => seconds.clone(),
+                _ => unreachable!("both sides are times"),
+            };
+
+            Value::Bool(compare(op.0, seconds(&lhs.0).cmp(&seconds(&rhs.0))))
+        }
+        (Eq | Ne | Lt | Le | Gt | Ge, _, _) => {
+            return Err(wrong_type(op.0, rhs, lhs.0.type_name()))
+        }
+        (Add | Sub, DateTime(_) | Duration(_), _) => {
+            return Err(wrong_type(op.0, rhs, "a duration"))
+        }
+        (Add | Sub, _, _) => return Err(wrong_type(op.0, lhs, "a duration")),
+        (_, DateTime(_) | Duration(_), _) => return Err(wrong_type(op.0, lhs, "a number")),
+        _ => return Err(wrong_type(op.0, rhs, "a number")),
+    })
+}
+
+fn evaluate_logical_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{And, Eq, Gt, Le, Lt, Or};
+
+    Ok(Value::Bool(match op.0 {
+        And => boolean(op.0, lhs)? && boolean(op.0, rhs)?,
+        Or => boolean(op.0, lhs)? || boolean(op.0, rhs)?,
+        Eq | BinaryOp::Ne => {
+            let equal = match (&lhs.0, &rhs.0) {
+                (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+                (Value::Bool(_), _) | (_, Value::Bool(_)) => {
+                    return Err(wrong_type(op.0, rhs, lhs.0.type_name()))
+                }
+                (Value::Complex(_), _) | (_, Value::Complex(_)) => {
+                    lhs.0.to_complex() == rhs.0.to_complex()
+                }
+                _ => real(op.0, lhs)? == real(op.0, rhs)?,
+            };
+
+            equal == (op.0 == Eq)
+        }
+        _ => {
+            let ordering = real(op.0, lhs)?.cmp(real(op.0, rhs)?);
+
+            match op.0 {
+                Lt => ordering.is_lt(),
+                Le => ordering.is_le(),
+                Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }
+        }
+    }))
+}
+
+fn evaluate_interval_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{Add, Mul, PlusMinus, Sub};
+
+    let span = rhs.1;
+    let lhs = interval(op.0, lhs)?;
+
+    Ok(Value::from_interval(match (op.0, &rhs.0) {
+        // Percentages are added like on a desk calculator, and a percentage tolerance is
+        // relative to the value it is applied to.
+        (Add, Value::Percent(percent)) => lhs * Interval::point(Rational::from(1u32) + percent),
+        (Sub, Value::Percent(percent)) => lhs * Interval::point(Rational::from(1u32) - percent),
+        (PlusMinus, Value::Percent(percent)) => {
+            let radius = percent.abs() * lhs.magnitude();
+
+            lhs + Interval::around(&Rational::ZERO, &radius)
+        }
+        (PlusMinus, _) => {
+            let radius = interval(op.0, rhs)?.magnitude();
+
+            lhs + Interval::around(&Rational::ZERO, &radius)
+        }
+        (Add, _) => lhs + interval(op.0, rhs)?,
+        (Sub, _) => lhs - interval(op.0, rhs)?,
+        (Mul, _) => lhs * interval(op.0, rhs)?,
+        _ => lhs
+            .checked_div(&interval(op.0, rhs)?)
+            .ok_or_else(|| Error::InvalidInterval {
+                operator: op.0.to_string(),
+                reason: "This interval contains zero",
+                span,
+            })?,
+    }))
+}
+
+fn evaluate_uncertain_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+    next_source: &Cell<u64>,
+) -> Result<Value, Error> {
+    use BinaryOp::{Add, Mul, Sub, Uncertainty};
+
+    let span = rhs.1;
+    let lhs = uncertain(op.0, lhs)?;
+    let source = || next_source.replace(next_source.get() + 1);
+
+    Ok(Value::from_uncertain(match (op.0, &rhs.0) {
+        (Add, Value::Percent(percent)) => lhs * Uncertain::exact(Rational::from(1u32) + percent),
+        (Sub, Value::Percent(percent)) => lhs * Uncertain::exact(Rational::from(1u32) - percent),
+        // Every `+-` is a new source of error, so uncertainties of the same value add in
+        // quadrature.
+        (Uncertainty, Value::Percent(percent)) => {
+            let uncertainty = percent * &lhs.value;
+
+            lhs + Uncertain::new(Rational::ZERO, &uncertainty, source())
+        }
+        (Uncertainty, _) => lhs + Uncertain::new(Rational::ZERO, real(op.0, rhs)?, source()),
+        (Add, _) => lhs + uncertain(op.0, rhs)?,
+        (Sub, _) => lhs - uncertain(op.0, rhs)?,
+        (Mul, _) => lhs * uncertain(op.0, rhs)?,
+        _ => lhs
+            .checked_div(&uncertain(op.0, rhs)?)
+            .ok_or(Error::DivisionByZero(span))?,
+    }))
+}
+
+fn evaluate_integer_op(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    use BinaryOp::{BitAnd, BitOr, BitXor, IntDiv, Mod, Shl};
+
+    let span = rhs.1;
+    let (lhs, rhs) = (integer(op.0, lhs)?, integer(op.0, rhs)?);
+
+    Ok(Value::Number(Rational::from(match op.0 {
+        Mod => lhs.mod_op(rhs),
+        IntDiv => lhs.div_mod(rhs).0,
+        BitAnd => lhs & rhs,
+        BitOr => lhs | rhs,
+        BitXor => lhs ^ rhs,
+        _ => {
+            let amount = i64::try_from(&rhs)
+                .ok()
+                .filter(|amount| amount.unsigned_abs() <= MAX_SHIFT)
+                .ok_or(Error::ShiftOutOfRange(span))?;
+
+            if op.0 == Shl {
+                lhs << amount
+            } else {
+                lhs >> amount
+            }
+        }
+    })))
+}
+
+fn real(op: impl core::fmt::Display + Copy, value: &Spanned<Value>) -> Result<&Rational, Error> {
+    match &value.0 {
+        Value::Number(rational) | Value::Percent(rational) => Ok(rational),
+        Value::Complex(_) => Err(Error::NotReal {
+            operator: op.to_string(),
+            span: value.1,
+        }),
+        Value::Interval(_) => Err(Error::InvalidInterval {
+            operator: op.to_string(),
+            reason: "This value is an interval",
+            span: value.1,
+        }),
+        Value::Uncertain(_) => Err(Error::InvalidUncertain {
+            operator: op.to_string(),
+            reason: "This value has an uncertainty",
+            span: value.1,
+        }),
+        Value::Bool(_)
+        | Value::List(_)
+        | Value::Matrix(_)
+        | Value::DateTime(_)
+        | Value::Duration(_)
+        | Value::Money(..)
+        | Value::Data(..)
+        | Value::DataRate(..) => Err(wrong_type(op, value, "a number")),
+        Value::Factors(_) => unreachable!("factorizations are multiplied out before they are used"),
+    }
+}
+
+fn boolean(op: impl core::fmt::Display, value: &Spanned<Value>) -> Result<bool, Error> {
+    match &value.0 {
+        Value::Bool(value) => Ok(*value),
+        _ => Err(wrong_type(op, value, "a boolean")),
+    }
+}
+
+/// The elements of a list, where a matrix is the list of its rows.
+fn list(op: impl core::fmt::Display, value: &Spanned<Value>) -> Result<Cow<'_, [Value]>, Error> {
+    match &value.0 {
+        Value::List(list) => Ok(Cow::Borrowed(list)),
+        Value::Matrix(matrix) => Ok(Cow::Owned(rows(matrix))),
+        _ => Err(wrong_type(op, value, "a list")),
+    }
+}
+
+/// The elements of a list of real numbers.
+fn vector(
+    op: impl core::fmt::Display + Copy,
+    value: &Spanned<Value>,
+) -> Result<Vec<Rational>, Error> {
+    list(op, value)?
+        .iter()
+        .map(|element| real(op, &Spanned::new(element.clone(), value.1)).cloned())
+        .collect()
+}
+
+fn matrix(op: impl core::fmt::Display, value: &Spanned<Value>) -> Result<&Matrix, Error> {
+    match &value.0 {
+        Value::Matrix(matrix) => Ok(matrix),
+        _ => Err(wrong_type(op, value, "a matrix")),
+    }
+}
+
+fn wrong_type(
+    op: impl core::fmt::Display,
+    value: &Spanned<Value>,
+    expected: &'static str,
+) -> Error {
+    Error::WrongType {
+        operator: op.to_string(),
+        expected,
+        found: value.0.type_name(),
+        span: value.1,
+    }
+}
+
+fn interval(op: impl core::fmt::Display + Copy, value: &Spanned<Value>) -> Result<Interval, Error> {
+    match &value.0 {
+        Value::Interval(interval) => Ok(interval.clone()),
+        _ => real(op, value).map(|rational| Interval::point(rational.clone())),
+    }
+}
+
+fn uncertain(
+    op: impl core::fmt::Display + Copy,
+    value: &Spanned<Value>,
+) -> Result<Uncertain, Error> {
+    match &value.0 {
+        Value::Uncertain(uncertain) => Ok(uncertain.clone()),
+        _ => real(op, value).map(|rational| Uncertain::exact(rational.clone())),
+    }
+}
+
+fn integer(op: impl core::fmt::Display + Copy, value: &Spanned<Value>) -> Result<Integer, Error> {
+    real(op, value)
+        .ok()
+        .and_then(|rational| Integer::try_from(rational).ok())
+        .ok_or_else(|| Error::NonInteger {
+            operator: op.to_string(),
+            span: value.1,
+        })
+}
+
+/// The text of `help`.
+const HELP: &str = "\
+Syntax:
+  <expr> - Evaluate an expression and print the result
+  <var> = <expr> - Assign a value to a variable
+
+Numbers:
+  1_000, 6.022e23, 0x1.8p3 - Digit separators and exponents
+  0.1(6), 0.1_6... - Repeating decimals
+  3 + 4 * i - Complex numbers
+  9.81 ± 0.02, 100 ± 5% - Intervals with guaranteed bounds
+  5.0 +- 0.1, 5.0 +- 2% - Measurements with independent standard uncertainties
+  x - x, x * x - A measurement reused in a variable stays correlated with itself
+  5+-3, 5 + -3 - Adds a negative number, as `+-` needs spaces around it
+
+Lists:
+  [1, 2, 3] - A list, which operators apply to element by element
+  [1, 2, 3] * 2, [1, 2] + [3, 4] - A number is applied to every element
+  1..10 - The integers from 1 to 10, including both
+  xs[0], xs[-1] - The first and last elements of a list
+  sum(xs), product(xs), len(xs), mean(xs) - Combine the elements of a list
+
+Matrices:
+  [[1, 2], [3, 4]] - A matrix, written as a list of rows of numbers
+  A * B, A * [x, y] - Matrix products, where a list is a column vector
+  A + B, A - B, 2 * A, A / 2, A[0][1] - Sums, scaling and entries
+  len(A), sum(A), A[0] - A matrix is also the list of its rows
+  det(A), inv(A), transpose(A), rank(A) - Exact linear algebra
+  solve(A, b) - The solution x of A * x = b
+
+Statistics:
+  median(xs), mode(xs), min(xs), max(xs) - The middle, most common and extremes
+  percentile(xs, p) - The p-th percentile, interpolating between elements
+  variance(xs), stdev(xs) - The spread of a sample, divided by n - 1
+  pvariance(xs), pstdev(xs) - The spread of a whole population, divided by n
+  geomean(xs), harmean(xs) - The geometric and harmonic means
+  linreg(xs, ys) - The slope and intercept of the least squares line
+  stdev([1 min, 3 min]), max([1 GB, 2 GB]) - Durations, money and data keep their unit
+
+Dates and times:
+  2026-10-17, 2026-10-17T09:30Z - Dates and times in UTC, or with an offset like +02:00
+  now, today - The current time and date, fixed by starting with `--now <date>`
+  90 min, 1.5 h, 2 days, 1 week - Durations in ms, s, min, h, days or weeks
+  2026-12-25 - today, now + 90 min - Differences and moved dates, counting leap years
+  2026-10-17T09:30[Europe/Berlin] - A local time in a time zone
+
+Money:
+  120 EUR, 9.99 USD - Amounts in a currency, by its ISO 4217 code
+  120 EUR + 19% - Amounts in one currency add, compare and scale like numbers
+  120 EUR to USD - Convert with the rates from `--rates <file>`, like `USD,1.0712` for 1 EUR
+  base,USD - A line in the rates file for the currency they are given against, EUR by default
+
+Data:
+  4.7 GB, 16 GiB, 512 bit - Bits and bytes with SI (kB, MB) or IEC (KiB, MiB) prefixes
+  100 Mbit/s, 100 Mbps, 20 MB/min - Data rates
+  4.7 GB / (100 Mbit/s) - The time to transfer an amount at a rate
+  1 TB to GiB, 100 Mbit/s to MB/s - Convert between units
+
+Number theory:
+  factor(n) - The prime factorization, like 2^3 · 3 · 5
+  gcd(a, b), lcm(a, b) - Greatest common divisor and least common multiple
+  isprime(n), nextprime(n), totient(n) - Primality, the next prime and Euler's phi
+  modpow(b, e, m), modinv(a, m) - Modular powers and inverses
+  n!, binomial(n, k) - Factorials and binomial coefficients
+
+Functions:
+  sqrt(x) - Square root, complex for negative numbers
+  approx(x, d) - The closest fraction to x with a denominator of at most d
+  abs(z), arg(z), conj(z) - Magnitude, angle and conjugate of a complex number
+
+Operators:
+  + - * / - Arithmetic
+  mod % div - Remainder and integer division
+  15% - A percentage, `%` not followed by a number, name, `(`, `~`, `not` or `if`
+  a % -b, a% - b - Rejected as ambiguous, write `a mod -b` or `(a%) - b` instead
+  2 * 15%, -15%, 15%! - A percentage binds tighter than any other operator
+  200 + 15%, 200 - 15% - Add or subtract a percentage of a number
+  200 * 15%, 15% * 200 - A percentage of a number, while 50% * 15% is 7.5%
+  <a> as % of <b> - The percentage <a> is of <b>, looser than any operator
+  & | xor ~ << >> - Bitwise operations on integers
+  == != < <= > >= - Comparisons, looser than any other operator
+  and or not, true false - Logic, where `and` and `or` short-circuit
+  if c then a else b, c ? a : b - Conditionals, evaluating only the branch taken
+
+Output:
+  <expr> to bin|oct|dec|hex - Display the result in another base
+  <expr> to base <n> - Display the result in base <n>
+  <expr> to repeating - Display the exact decimal expansion
+  <expr> to fraction - Display the result as a fraction
+  <expr> to mixed - Display the result as a mixed number
+  <expr> to % - Display the result as a percentage
+  <expr> to days|h|min|... - Display a duration in one unit
+  <expr> to iso - Display a date or a duration in ISO 8601
+  <expr> to GiB|MB|Mbit/s|... - Display an amount of data or a data rate in one unit
+  <expr> in <zone>, <expr> to tz <zone> - Display a date in a zone, like \"Asia/Tokyo\"
+  <expr> to cf - Display the continued fraction, like [3; 7, 15, 1]
+  <expr> to polar - Display the magnitude and the angle in radians
+  <expr> to concise - Display an uncertainty in the last digits, like 12.3(4)
+
+Commands:
+  precision <p> - Set the precision of numbers to <p>
+  fullprecision - Use full precision for numbers
+  warnings on|off - Show or hide warnings
+  width u8|i32|...|off - Wrap integer results to a fixed width
+  explain <code> - Explain the error with the code <code>
+  help - Print this help message
+  exit - Exit the program
+";
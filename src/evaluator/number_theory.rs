@@ -0,0 +1,381 @@
+use super::{integer, wrong_type, Value};
+use crate::{diagnostics::error::Error, span::Spanned};
+use malachite::{
+    num::{
+        arithmetic::traits::{
+            BinomialCoefficient, DivisibleBy, Factorial, Gcd, Lcm, Mod, ModInverse, ModMul, ModPow,
+            Pow, UnsignedAbs,
+        },
+        basic::traits::{One, Zero},
+    },
+    Integer, Natural, Rational,
+};
+use std::collections::BTreeMap;
+
+/// The largest number whose factorial is computed, and the largest `k` for `binomial(n, k)`.
+const MAX_FACTORIAL: u64 = 100_000;
+
+/// The number of steps Pollard's rho takes before giving up on a factorization.
+const MAX_RHO_STEPS: u64 = 1 << 18;
+
+/// The number of steps Pollard's rho takes between checks for a divisor.
+const RHO_BATCH: u64 = 64;
+
+/// Trial division is used for every factor up to this bound.
+const TRIAL_BOUND: u32 = 1 << 12;
+
+/// Miller-Rabin with these bases is deterministic below 3.3 · 10^24, and a strong probable prime
+/// test above.
+const WITNESSES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// The prime factorization of a non-zero integer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Factors {
+    pub negative: bool,
+    /// The primes in increasing order with their exponents, empty for 1.
+    pub factors: Vec<(Natural, u64)>,
+}
+
+impl Factors {
+    pub fn value(&self) -> Rational {
+        let magnitude = self
+            .factors
+            .iter()
+            .map(|(prime, exponent)| prime.pow(*exponent))
+            .product::<Natural>();
+
+        if self.negative {
+            -Rational::from(magnitude)
+        } else {
+            Rational::from(magnitude)
+        }
+    }
+
+    /// Writes the factorization like `2^3 · 3 · 5`.
+    pub fn display(&self) -> String {
+        let sign = if self.negative { "-" } else { "" };
+
+        if self.factors.is_empty() {
+            return format!("{sign}1");
+        }
+
+        let factors = self
+            .factors
+            .iter()
+            .map(|(prime, exponent)| {
+                if *exponent == 1 {
+                    prime.to_string()
+                } else {
+                    format!("{prime}^{exponent}")
+                }
+            })
+            .collect::<Vec<_>>();
+
+        format!("{sign}{}", factors.join(" · "))
+    }
+}
+
+pub fn call(name: &'static str, args: &[Spanned<Value>]) -> Result<Value, Error> {
+    let number = |n: Integer| Value::Number(Rational::from(n));
+
+    Ok(match args {
+        [a, b] if name == "gcd" => number(Integer::from(
+            integer(name, a)?
+                .unsigned_abs()
+                .gcd(integer(name, b)?.unsigned_abs()),
+        )),
+        [a, b] if name == "lcm" => number(Integer::from(
+            integer(name, a)?
+                .unsigned_abs()
+                .lcm(integer(name, b)?.unsigned_abs()),
+        )),
+        [n, k] if name == "binomial" => number(binomial(name, n, k)?),
+        [a, m] if name == "modinv" => {
+            let m = modulus(name, m)?;
+
+            number(Integer::from(inverse(name, a, &m)?))
+        }
+        [b, e, m] if name == "modpow" => {
+            let m = modulus(name, m)?;
+            let exponent = integer(name, e)?;
+
+            let base = if exponent < 0u32 {
+                inverse(name, b, &m)?
+            } else {
+                reduce(integer(name, b)?, &m)
+            };
+
+            number(Integer::from(base.mod_pow(exponent.unsigned_abs(), m)))
+        }
+        [n] if name == "isprime" => {
+            Value::Bool(Natural::try_from(integer(name, n)?).is_ok_and(|n| is_prime(&n)))
+        }
+        [n] if name == "nextprime" => number(Integer::from(next_prime(&integer(name, n)?))),
+        [n] if name == "factor" => {
+            let value = integer(name, n)?;
+
+            if value == 0u32 {
+                return Err(Error::ArgumentOutOfRange {
+                    name,
+                    expected: "a non-zero integer",
+                    span: n.1,
+                });
+            }
+
+            Value::Factors(Factors {
+                negative: value < 0u32,
+                factors: factorize(name, n, value.unsigned_abs())?,
+            })
+        }
+        [n] if name == "totient" => {
+            let value = Natural::try_from(integer(name, n)?)
+                .ok()
+                .filter(|value| *value != 0u32)
+                .ok_or(Error::ArgumentOutOfRange {
+                    name,
+                    expected: "a positive integer",
+                    span: n.1,
+                })?;
+
+            let totient = factorize(name, n, value)?
+                .into_iter()
+                .map(|(prime, exponent)| (&prime - Natural::ONE) * prime.pow(exponent - 1))
+                .product::<Natural>();
+
+            number(Integer::from(totient))
+        }
+        _ => unreachable!("`{name}` is not a number theory function"),
+    })
+}
+
+/// The factorial of a non-negative integer, written `n!`.
+pub fn factorial(n: &Spanned<Value>) -> Result<Value, Error> {
+    if matches!(n.0, Value::Bool(_)) {
+        return Err(wrong_type("!", n, "a number"));
+    }
+
+    let value = u64::try_from(&integer("!", n)?)
+        .ok()
+        .filter(|value| *value <= MAX_FACTORIAL)
+        .ok_or(Error::ArgumentOutOfRange {
+            name: "!",
+            expected: "an integer from 0 to 100000",
+            span: n.1,
+        })?;
+
+    Ok(Value::Number(Rational::from(Natural::factorial(value))))
+}
+
+/// `n` choose `k`, which is zero for negative `k` and follows the usual extension to negative
+/// `n`.
+fn binomial(name: &'static str, n: &Spanned<Value>, k: &Spanned<Value>) -> Result<Integer, Error> {
+    let (n_value, k_value) = (integer(name, n)?, integer(name, k)?);
+
+    if k_value < 0u32 || (n_value >= 0u32 && k_value > n_value) {
+        return Ok(Integer::ZERO);
+    }
+
+    // The result is the same for `k` and `n - k`, so only the smaller one is limited.
+    let smaller = if n_value >= 0u32 {
+        (&n_value - &k_value).min(k_value.clone())
+    } else {
+        k_value.clone()
+    };
+
+    if smaller > MAX_FACTORIAL {
+        return Err(Error::ArgumentOutOfRange {
+            name,
+            expected: "at most 100000 terms",
+            span: k.1,
+        });
+    }
+
+    Ok(Integer::binomial_coefficient(n_value, k_value))
+}
+
+fn modulus(name: &'static str, m: &Spanned<Value>) -> Result<Natural, Error> {
+    Natural::try_from(integer(name, m)?)
+        .ok()
+        .filter(|m| *m != 0u32)
+        .ok_or(Error::ArgumentOutOfRange {
+            name,
+            expected: "a positive modulus",
+            span: m.1,
+        })
+}
+
+/// The remainder of `a` modulo `m`, which is never negative.
+fn reduce(a: Integer, m: &Natural) -> Natural {
+    Natural::try_from(a.mod_op(Integer::from(m.clone()))).unwrap()
+}
+
+fn inverse(name: &'static str, a: &Spanned<Value>, m: &Natural) -> Result<Natural, Error> {
+    if *m == 1u32 {
+        return Ok(Natural::ZERO);
+    }
+
+    reduce(integer(name, a)?, m)
+        .mod_inverse(m.clone())
+        .ok_or(Error::ArgumentOutOfRange {
+            name,
+            expected: "a number coprime to the modulus",
+            span: a.1,
+        })
+}
+
+/// Miller-Rabin with the bases in [`WITNESSES`].
+fn is_prime(n: &Natural) -> bool {
+    if *n < 2u32 {
+        return false;
+    }
+
+    for witness in WITNESSES {
+        if *n == witness {
+            return true;
+        }
+
+        if n.divisible_by(Natural::from(witness)) {
+            return false;
+        }
+    }
+
+    let n_minus_one = n - Natural::ONE;
+    let shift = n_minus_one.trailing_zeros().unwrap();
+    let odd = &n_minus_one >> shift;
+
+    WITNESSES.iter().all(|&witness| {
+        let mut x = Natural::from(witness).mod_pow(&odd, n);
+
+        if x == 1u32 || x == n_minus_one {
+            return true;
+        }
+
+        for _ in 1..shift {
+            x = x.clone().mod_mul(&x, n);
+
+            if x == n_minus_one {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+/// The smallest prime greater than `n`.
+fn next_prime(n: &Integer) -> Natural {
+    let mut candidate = Natural::try_from(n + Integer::ONE).unwrap_or(Natural::ZERO);
+
+    while !is_prime(&candidate) {
+        candidate += Natural::ONE;
+    }
+
+    candidate
+}
+
+/// The primes dividing `n` in increasing order with their exponents, found by trial division and
+/// then Pollard's rho.
+fn factorize(
+    name: &'static str,
+    arg: &Spanned<Value>,
+    mut n: Natural,
+) -> Result<Vec<(Natural, u64)>, Error> {
+    let mut factors = BTreeMap::new();
+
+    for divisor in (2..TRIAL_BOUND).filter(|d| *d == 2 || d % 2 == 1) {
+        let divisor = Natural::from(divisor);
+
+        if &divisor * &divisor > n {
+            break;
+        }
+
+        while (&n).divisible_by(&divisor) {
+            n /= &divisor;
+            *factors.entry(divisor.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let mut steps = MAX_RHO_STEPS;
+    let mut pending = vec![n];
+
+    while let Some(n) = pending.pop() {
+        if n == 1u32 {
+            continue;
+        }
+
+        if is_prime(&n) {
+            *factors.entry(n).or_insert(0) += 1;
+            continue;
+        }
+
+        let divisor = rho(&n, &mut steps).ok_or(Error::ArgumentOutOfRange {
+            name,
+            expected: "a number with smaller prime factors",
+            span: arg.1,
+        })?;
+
+        pending.push(&n / &divisor);
+        pending.push(divisor);
+    }
+
+    Ok(factors.into_iter().collect())
+}
+
+/// A non-trivial divisor of a composite number, unless none is found in the remaining `steps`.
+///
+/// This is Brent's variant, which takes one gcd per batch of steps and only retraces a batch
+/// step by step when its gcd is not 1.
+fn rho(n: &Natural, steps: &mut u64) -> Option<Natural> {
+    let distance = |x: &Natural, y: &Natural| if x > y { x - y } else { y - x };
+
+    'retry: for c in 1u32.. {
+        let next = |x: &Natural| (x * x + Natural::from(c)) % n;
+        let (mut y, mut power) = (Natural::from(2u32), 1);
+
+        loop {
+            let x = y.clone();
+            let mut taken = 0;
+
+            while taken < power {
+                let batch = RHO_BATCH.min(power - taken);
+                *steps = steps.checked_sub(batch)?;
+
+                let start = y.clone();
+                let mut product = Natural::ONE;
+
+                for _ in 0..batch {
+                    y = next(&y);
+                    product = product * distance(&x, &y) % n;
+                }
+
+                taken += batch;
+
+                if product.gcd(n) == 1u32 {
+                    continue;
+                }
+
+                y = start;
+
+                let divisor = loop {
+                    y = next(&y);
+
+                    let divisor = distance(&x, &y).gcd(n);
+
+                    if divisor != 1u32 {
+                        break divisor;
+                    }
+                };
+
+                if divisor == *n {
+                    continue 'retry;
+                }
+
+                return Some(divisor);
+            }
+
+            power *= 2;
+        }
+    }
+
+    None
+}
@@ -0,0 +1,420 @@
+use super::{evaluate_binary_op, functions::root, functions::sqrt, list, real, wrong_type, Value};
+use crate::{
+    currency::Currency,
+    data::{DataUnit, RateUnit},
+    diagnostics::error::Error,
+    parser::ast::BinaryOp,
+    span::Spanned,
+};
+use chumsky::span::Span as _;
+use core::cell::Cell;
+use malachite::{
+    num::{
+        arithmetic::traits::Floor,
+        basic::traits::{One, Zero},
+    },
+    Rational,
+};
+
+/// The elements of a list as real numbers, in the base unit of their kind.
+///
+/// Results have the kind of the elements, so the spread of some durations is a duration. Numbers
+/// and percentages can be mixed, and results are percentages only when every element is.
+struct Sample {
+    values: Vec<Rational>,
+    kind: Kind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Percent,
+    Duration,
+    Money(Currency),
+    Data(DataUnit),
+    DataRate(RateUnit),
+}
+
+impl Kind {
+    const fn of(value: &Value) -> Self {
+        match value {
+            Value::Percent(_) => Self::Percent,
+            Value::Duration(_) => Self::Duration,
+            Value::Money(_, currency) => Self::Money(*currency),
+            Value::Data(_, unit) => Self::Data(*unit),
+            Value::DataRate(_, unit) => Self::DataRate(*unit),
+            _ => Self::Number,
+        }
+    }
+
+    const fn is_number(self) -> bool {
+        matches!(self, Self::Number | Self::Percent)
+    }
+
+    /// Reads an element, which has to be of this kind.
+    fn read(self, name: &'static str, value: &Spanned<Value>) -> Result<Rational, Error> {
+        match (self, &value.0) {
+            (Self::Number | Self::Percent, _) => real(name, value).cloned(),
+            (Self::Duration, Value::Duration(rational))
+            | (Self::Data(_), Value::Data(rational, _))
+            | (Self::DataRate(_), Value::DataRate(rational, _)) => Ok(rational.clone()),
+            (Self::Money(currency), Value::Money(rational, other)) if currency == *other => {
+                Ok(rational.clone())
+            }
+            (Self::Money(currency), Value::Money(_, other)) => Err(Error::DimensionMismatch {
+                operator: name.into(),
+                lhs: currency.to_string(),
+                rhs: other.to_string(),
+                span: value.1,
+            }),
+            _ => Err(wrong_type(
+                name,
+                value,
+                self.value(Rational::ZERO).type_name(),
+            )),
+        }
+    }
+
+    const fn value(self, rational: Rational) -> Value {
+        match self {
+            Self::Number => Value::Number(rational),
+            Self::Percent => Value::Percent(rational),
+            Self::Duration => Value::Duration(rational),
+            Self::Money(currency) => Value::Money(rational, currency),
+            Self::Data(unit) => Value::Data(rational, unit),
+            Self::DataRate(unit) => Value::DataRate(rational, unit),
+        }
+    }
+}
+
+impl Sample {
+    /// Reads a list with at least `minimum` elements, all of which are real.
+    fn new(name: &'static str, x: &Spanned<Value>, minimum: usize) -> Result<Self, Error> {
+        let elements = list(name, x)?;
+
+        if elements.len() < minimum {
+            return Err(Error::InvalidList {
+                operator: name.into(),
+                reason: if elements.is_empty() {
+                    "This list is empty"
+                } else {
+                    "This list has too few elements"
+                },
+                span: x.1,
+            });
+        }
+
+        let kind = match Kind::of(&elements[0]) {
+            Kind::Percent
+                if !elements
+                    .iter()
+                    .all(|value| matches!(value, Value::Percent(_))) =>
+            {
+                Kind::Number
+            }
+            kind => kind,
+        };
+
+        let values = elements
+            .iter()
+            .map(|value| kind.read(name, &Spanned::new(value.clone(), x.1)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { values, kind })
+    }
+
+    fn sorted(mut self) -> Self {
+        self.values.sort();
+        self
+    }
+
+    fn len(&self) -> Rational {
+        Rational::from(self.values.len())
+    }
+
+    fn mean(&self) -> Rational {
+        self.values.iter().sum::<Rational>() / self.len()
+    }
+
+    /// The sum of the squared differences from the mean, divided by `n` for a population and by
+    /// `n - 1` for a sample.
+    fn variance(&self, population: bool) -> Rational {
+        let mean = self.mean();
+        let squares = self
+            .values
+            .iter()
+            .map(|value| (value - &mean) * (value - &mean))
+            .sum::<Rational>();
+
+        if population {
+            squares / self.len()
+        } else {
+            squares / (self.len() - Rational::from(1u32))
+        }
+    }
+
+    /// The value at `fraction` of the way through the sorted values, interpolating linearly
+    /// between neighbours.
+    fn quantile(&self, fraction: &Rational) -> Rational {
+        let rank = fraction * (self.len() - Rational::from(1u32));
+        let lower = rank.clone().floor();
+        let weight = rank - Rational::from(lower.clone());
+        let index = usize::try_from(&lower).unwrap();
+
+        if weight == 0u32 {
+            self.values[index].clone()
+        } else {
+            &self.values[index] + (&self.values[index + 1] - &self.values[index]) * weight
+        }
+    }
+
+    /// Wraps a result with the same kind as the elements.
+    const fn value(&self, rational: Rational) -> Value {
+        self.kind.value(rational)
+    }
+}
+
+// Sums, products and means work on any values that can be added, so they are not limited to
+// real numbers like the rest.
+pub fn sum(name: Spanned<&'static str>, x: &Spanned<Value>) -> Result<Value, Error> {
+    // Durations, amounts of money and data can only be added to their own kind, so their sum
+    // starts from nothing of that kind.
+    let zero = match list(name.0, x)?.first() {
+        Some(Value::Duration(_)) => Value::Duration(Rational::ZERO),
+        Some(Value::Money(_, currency)) => Value::Money(Rational::ZERO, *currency),
+        Some(Value::Data(_, unit)) => Value::Data(Rational::ZERO, *unit),
+        Some(Value::DataRate(_, unit)) => Value::DataRate(Rational::ZERO, *unit),
+        _ => Value::Number(Rational::ZERO),
+    };
+
+    fold(name, BinaryOp::Add, zero, x)
+}
+
+pub fn product(name: Spanned<&'static str>, x: &Spanned<Value>) -> Result<Value, Error> {
+    fold(name, BinaryOp::Mul, Value::Number(Rational::ONE), x)
+}
+
+pub fn mean(name: Spanned<&'static str>, x: &Spanned<Value>) -> Result<Value, Error> {
+    let length = list(name.0, x)?.len();
+
+    if length == 0 {
+        return Err(Error::InvalidList {
+            operator: name.0.into(),
+            reason: "This list is empty",
+            span: x.1,
+        });
+    }
+
+    combine(
+        Spanned::new(BinaryOp::Div, name.1),
+        &Spanned::new(sum(name, x)?, x.1),
+        &Spanned::new(Value::Number(Rational::from(length)), x.1),
+    )
+}
+
+/// Applies an arithmetic operator, which never takes a new `+-` measurement, so it needs no
+/// sources of error.
+fn combine(
+    op: Spanned<BinaryOp>,
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+) -> Result<Value, Error> {
+    evaluate_binary_op(op, lhs, rhs, &Cell::default())
+}
+
+/// Combines the elements of a list with `op`, starting from `init`.
+fn fold(
+    name: Spanned<&'static str>,
+    op: BinaryOp,
+    init: Value,
+    x: &Spanned<Value>,
+) -> Result<Value, Error> {
+    list(name.0, x)?.iter().try_fold(init, |acc, value| {
+        combine(
+            Spanned::new(op, name.1),
+            &Spanned::new(acc, x.1),
+            &Spanned::new(value.clone(), x.1),
+        )
+    })
+}
+
+pub fn median(name: &'static str, x: &Spanned<Value>) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, 1)?.sorted();
+
+    Ok(sample.value(sample.quantile(&(Rational::from(1u32) / Rational::from(2u32)))))
+}
+
+/// Accepts `90` and `90%` alike for the 90th percentile.
+pub fn percentile(
+    name: &'static str,
+    x: &Spanned<Value>,
+    p: &Spanned<Value>,
+) -> Result<Value, Error> {
+    let fraction = match &p.0 {
+        Value::Percent(fraction) => fraction.clone(),
+        _ => real(name, p)? / Rational::from(100u32),
+    };
+
+    if !(0u32..=1u32).contains(&fraction) {
+        return Err(Error::ArgumentOutOfRange {
+            name,
+            expected: "a percentile from 0 to 100",
+            span: p.1,
+        });
+    }
+
+    let sample = Sample::new(name, x, 1)?.sorted();
+
+    Ok(sample.value(sample.quantile(&fraction)))
+}
+
+/// The most common element, where ties go to the smallest.
+pub fn mode(name: &'static str, x: &Spanned<Value>) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, 1)?.sorted();
+
+    let mut best = (&sample.values[0], 0);
+    let mut run = (&sample.values[0], 0);
+
+    for value in &sample.values {
+        run = if *value == *run.0 {
+            (run.0, run.1 + 1)
+        } else {
+            (value, 1)
+        };
+
+        if run.1 > best.1 {
+            best = run;
+        }
+    }
+
+    Ok(sample.value(best.0.clone()))
+}
+
+pub fn extreme(name: &'static str, x: &Spanned<Value>, maximum: bool) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, 1)?;
+
+    let value = if maximum {
+        sample.values.iter().max()
+    } else {
+        sample.values.iter().min()
+    };
+
+    Ok(sample.value(value.unwrap().clone()))
+}
+
+/// The variance is in squared units, so it is never a percentage, and squared durations or
+/// amounts cannot be written at all.
+pub fn variance(name: &'static str, x: &Spanned<Value>, population: bool) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, if population { 1 } else { 2 })?;
+
+    if !sample.kind.is_number() {
+        return Err(Error::InvalidList {
+            operator: name.into(),
+            reason: "The variance of these is in squared units, use `stdev` instead",
+            span: x.1,
+        });
+    }
+
+    Ok(Value::Number(sample.variance(population)))
+}
+
+pub fn stdev(name: &'static str, x: &Spanned<Value>, population: bool) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, if population { 1 } else { 2 })?;
+
+    Ok(sample.value(sqrt(&sample.variance(population))))
+}
+
+pub fn geomean(name: &'static str, x: &Spanned<Value>) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, 1)?;
+
+    if sample.values.iter().any(|value| *value < 0u32) {
+        return Err(Error::InvalidList {
+            operator: name.into(),
+            reason: "This list contains negative numbers",
+            span: x.1,
+        });
+    }
+
+    let product = sample.values.iter().product::<Rational>();
+
+    Ok(sample.value(root(&product, sample.values.len() as u64)))
+}
+
+pub fn harmean(name: &'static str, x: &Spanned<Value>) -> Result<Value, Error> {
+    let sample = Sample::new(name, x, 1)?;
+
+    if sample.values.iter().any(|value| *value == 0u32) {
+        return Err(Error::InvalidList {
+            operator: name.into(),
+            reason: "This list contains zero",
+            span: x.1,
+        });
+    }
+
+    let reciprocals = sample
+        .values
+        .iter()
+        .map(|value| Rational::from(1u32) / value)
+        .sum::<Rational>();
+
+    if reciprocals == 0u32 {
+        return Err(Error::DivisionByZero(x.1));
+    }
+
+    Ok(sample.value(sample.len() / reciprocals))
+}
+
+/// The least squares line through the points, as `[slope, intercept]`, where `xs` are numbers
+/// so the slope has the kind of `ys`.
+pub fn linreg(
+    name: &'static str,
+    xs: &Spanned<Value>,
+    ys: &Spanned<Value>,
+) -> Result<Value, Error> {
+    let (x, y) = (Sample::new(name, xs, 2)?, Sample::new(name, ys, 2)?);
+
+    if !x.kind.is_number() {
+        return Err(Error::InvalidList {
+            operator: name.into(),
+            reason: "The slope would be in a ratio of units, so these have to be numbers",
+            span: xs.1,
+        });
+    }
+
+    if x.values.len() != y.values.len() {
+        return Err(Error::LengthMismatch {
+            operator: name.into(),
+            lhs: x.values.len(),
+            rhs: y.values.len(),
+            span: xs.1.union(ys.1),
+        });
+    }
+
+    let (x_mean, y_mean) = (x.mean(), y.mean());
+
+    let (mut xx, mut xy) = (Rational::ZERO, Rational::ZERO);
+
+    for (x, y) in x.values.iter().zip(&y.values) {
+        xx += (x - &x_mean) * (x - &x_mean);
+        xy += (x - &x_mean) * (y - &y_mean);
+    }
+
+    if xx == 0u32 {
+        return Err(Error::InvalidList {
+            operator: name.into(),
+            reason: "All of these values are equal",
+            span: xs.1,
+        });
+    }
+
+    let slope = xy / xx;
+    let intercept = y_mean - &slope * x_mean;
+
+    let slope = if y.kind.is_number() {
+        Value::Number(slope)
+    } else {
+        y.value(slope)
+    };
+
+    Ok(Value::List(vec![slope, y.value(intercept)]))
+}
@@ -0,0 +1,232 @@
+use super::functions::sqrt;
+use malachite::{
+    num::{
+        arithmetic::traits::{Abs, FloorLogBase, Pow, RoundToMultiple},
+        basic::traits::{One, Zero},
+    },
+    rounding_modes::RoundingMode,
+    Integer, Rational,
+};
+use std::collections::BTreeMap;
+
+/// A measured value with a standard uncertainty.
+///
+/// The uncertainty is kept as its first-order sensitivity to every independent source of error,
+/// like each `+-` measurement, so a value combined with itself stays correlated and `x - x` is
+/// exactly zero. The sensitivities stay exact, and so does the variance they add up to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Uncertain {
+    pub value: Rational,
+    sources: BTreeMap<u64, Rational>,
+}
+
+impl Uncertain {
+    pub const fn exact(value: Rational) -> Self {
+        Self {
+            value,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// A value with an uncertainty from a source of error, which is independent of every other
+    /// source as long as no other value uses the same one.
+    pub fn new(value: Rational, uncertainty: &Rational, source: u64) -> Self {
+        let mut sources = BTreeMap::new();
+
+        if *uncertainty != 0u32 {
+            sources.insert(source, uncertainty.clone());
+        }
+
+        Self { value, sources }
+    }
+
+    pub fn variance(&self) -> Rational {
+        self.sources
+            .values()
+            .map(|sensitivity| sensitivity * sensitivity)
+            .sum()
+    }
+
+    pub fn uncertainty(&self) -> Rational {
+        sqrt(&self.variance())
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// The sensitivities of `self * a + rhs * b`.
+    fn combine(&self, a: &Rational, rhs: &Self, b: &Rational) -> BTreeMap<u64, Rational> {
+        let mut sources = self
+            .sources
+            .iter()
+            .map(|(source, sensitivity)| (*source, sensitivity * a))
+            .collect::<BTreeMap<_, _>>();
+
+        for (source, sensitivity) in &rhs.sources {
+            *sources.entry(*source).or_default() += sensitivity * b;
+        }
+
+        sources.retain(|_, sensitivity| *sensitivity != 0u32);
+
+        sources
+    }
+
+    /// Scales every sensitivity by the derivative of a function at the value.
+    fn scaled(&self, value: Rational, derivative: &Rational) -> Self {
+        Self {
+            value,
+            sources: self.combine(derivative, &Self::exact(Rational::ZERO), derivative),
+        }
+    }
+
+    /// Divides by `rhs`, unless its value is zero.
+    pub fn checked_div(self, rhs: &Self) -> Option<Self> {
+        if rhs.value == 0u32 {
+            return None;
+        }
+
+        let square = &rhs.value * &rhs.value;
+
+        Some(Self {
+            sources: self.combine(&(Rational::ONE / &rhs.value), rhs, &-(&self.value / square)),
+            value: self.value / &rhs.value,
+        })
+    }
+
+    /// The square root, unless the value is negative, or zero with an uncertainty.
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.value < 0u32 || (self.value == 0u32 && !self.is_exact()) {
+            return None;
+        }
+
+        let root = sqrt(&self.value);
+
+        Some(if self.is_exact() {
+            Self::exact(root)
+        } else {
+            let derivative = Rational::ONE / (Rational::from(2u32) * &root);
+
+            self.scaled(root, &derivative)
+        })
+    }
+
+    pub fn abs(&self) -> Self {
+        let sign = if self.value < 0u32 {
+            -Rational::ONE
+        } else {
+            Rational::ONE
+        };
+
+        self.scaled((&self.value).abs(), &sign)
+    }
+
+    /// Rounds the uncertainty to two significant digits if it starts with a 1 or a 2, and to one
+    /// otherwise, and the value to the same decimal place.
+    ///
+    /// The concise form writes the uncertainty in units of the last digit, like `12.3(4)`.
+    pub fn display(&self, concise: bool) -> String {
+        let uncertainty = self.uncertainty();
+
+        if uncertainty == 0u32 {
+            return fixed(&self.value, 0);
+        }
+
+        let ten = Rational::from(10u32);
+        let exponent = (&uncertainty).floor_log_base(&ten);
+        let leading = (&uncertainty / (&ten).pow(exponent))
+            .round_to_multiple(Rational::from(1u32), RoundingMode::Floor);
+        let place = if leading.0 <= 2u32 {
+            exponent - 1
+        } else {
+            exponent
+        };
+
+        if concise {
+            let digits = if place >= 0 {
+                fixed(&uncertainty, place)
+            } else {
+                round(&uncertainty, place).to_string()
+            };
+
+            format!("{}({digits})", fixed(&self.value, place))
+        } else {
+            format!(
+                "{} ± {}",
+                fixed(&self.value, place),
+                fixed(&uncertainty, place)
+            )
+        }
+    }
+}
+
+/// Rounds `rational` to the nearest multiple of `10^place`, in units of `10^place`.
+fn round(rational: &Rational, place: i64) -> Integer {
+    let scaled = rational / Rational::from(10u32).pow(place);
+
+    Integer::try_from(
+        &scaled
+            .round_to_multiple(Rational::from(1u32), RoundingMode::Nearest)
+            .0,
+    )
+    .unwrap()
+}
+
+/// Formats `rational` rounded to a multiple of `10^place`.
+fn fixed(rational: &Rational, place: i64) -> String {
+    let digits = round(rational, place);
+
+    if place >= 0 {
+        return (digits * Integer::from(10u32).pow(place.unsigned_abs())).to_string();
+    }
+
+    let decimals = usize::try_from(place.unsigned_abs()).unwrap();
+    let sign = if digits < 0u32 { "-" } else { "" };
+    let digits = format!("{:0>width$}", (&digits).abs(), width = decimals + 1);
+    let (before, after) = digits.split_at(digits.len() - decimals);
+
+    format!("{sign}{before}.{after}")
+}
+
+impl core::ops::Add for Uncertain {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            sources: self.combine(&Rational::ONE, &rhs, &Rational::ONE),
+            value: self.value + rhs.value,
+        }
+    }
+}
+
+impl core::ops::Sub for Uncertain {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            sources: self.combine(&Rational::ONE, &rhs, &-Rational::ONE),
+            value: self.value - rhs.value,
+        }
+    }
+}
+
+impl core::ops::Mul for Uncertain {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            sources: self.combine(&rhs.value, &rhs, &self.value),
+            value: self.value * rhs.value,
+        }
+    }
+}
+
+impl core::ops::Neg for Uncertain {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        let value = -&self.value;
+
+        self.scaled(value, &-Rational::ONE)
+    }
+}
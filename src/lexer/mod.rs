@@ -1,81 +1,96 @@
-use crate::span::Span;
+use crate::{
+    diagnostics::error::{Error, SyntaxError},
+    span::Span,
+    time::DateTime,
+};
 use chumsky::{input::WithContext, prelude::*};
 use token::{Kw, Punc, Radix, Simple, Spanned, Token};
 
 pub mod token;
 
+pub const MAX_EXPONENT: u64 = 100_000;
+
 type ParserInput<'src> = WithContext<Span, &'src str>;
 
-type ParserExtra<'src> = extra::Err<Rich<'src, char, Span, &'src str>>;
+type ParserExtra<'src> = extra::Err<SyntaxError<'src, char, &'src str>>;
 
 pub fn lexer<'src>(
 ) -> impl Parser<'src, ParserInput<'src>, Vec<Spanned<Token<'src>>>, ParserExtra<'src>> {
-    recursive(|tokens| {
+    let tokens = recursive(|tokens| {
         let ident = text::ascii::ident().map(Simple::Identifier).boxed();
 
-        let number_base = |radix: Radix| {
-            text::int(radix.to_u32())
-                .then(
-                    just('.')
-                        .ignore_then(text::digits(radix.to_u32()).to_slice())
-                        .or_not(),
-                )
-                .map(move |(before, after)| Simple::Number {
-                    before,
-                    after,
-                    radix,
-                })
-                .boxed()
-        };
-
-        let binary_number = just("0b").ignore_then(number_base(Radix::Binary));
-        let octal_number = just("0o").ignore_then(number_base(Radix::Octal));
-        let hexadecimal_number = just("0x").ignore_then(number_base(Radix::Hexadecimal));
-
-        let decimal_number = number_base(Radix::Decimal);
-
         let keyword = choice((
             text::keyword("to").to(Kw::To),
+            text::keyword("in").to(Kw::In),
             text::keyword("precision").to(Kw::Precision),
             text::keyword("fullprecision").to(Kw::FullPrecision),
             text::keyword("help").to(Kw::Help),
             text::keyword("exit").to(Kw::Exit),
             text::keyword("vars").to(Kw::Vars),
+            text::keyword("explain").to(Kw::Explain),
+            text::keyword("warnings").to(Kw::Warnings),
+            text::keyword("width").to(Kw::Width),
+            text::keyword("mod").to(Kw::Mod),
+            text::keyword("div").to(Kw::Div),
+            text::keyword("xor").to(Kw::Xor),
+            text::keyword("as").to(Kw::As),
+            text::keyword("of").to(Kw::Of),
+            text::keyword("and").to(Kw::And),
+            text::keyword("or").to(Kw::Or),
+            text::keyword("not").to(Kw::Not),
+            text::keyword("true").to(Kw::True),
+            text::keyword("false").to(Kw::False),
+            text::keyword("if").to(Kw::If),
+            text::keyword("then").to(Kw::Then),
+            text::keyword("else").to(Kw::Else),
         ))
         .map(Simple::Kw)
         .boxed();
 
-        let punctuation = choice((
-            just('+').to(Punc::Plus),
-            just('-').to(Punc::Minus),
-            just('*').to(Punc::Star),
-            just('/').to(Punc::Slash),
-            just('=').to(Punc::Equals),
-        ))
-        .map(Simple::Punc)
-        .boxed();
-
         let simple = choice((
             keyword,
             ident,
-            binary_number,
-            octal_number,
-            hexadecimal_number,
-            decimal_number,
-            punctuation,
+            date_time(),
+            number(),
+            string(),
+            punctuation(),
         ))
         .map(Token::Simple)
         .boxed();
 
-        let parenthesised = tokens
-            .clone()
-            .delimited_by(just('('), just(')'))
-            .map(Token::Parentheses)
-            .boxed();
+        let delimited = |open, close| {
+            just(open)
+                .ignore_then(tokens.clone())
+                .then(just(close).or_not())
+                .validate(move |(tokens, close), e, emitter| {
+                    if close.is_none() {
+                        emitter.emit(SyntaxError::Invalid(Error::UnclosedDelimiter {
+                            delimiter: open,
+                            span: e.span(),
+                        }));
+                    }
+
+                    tokens
+                })
+                .boxed()
+        };
 
-        let curly_braces = tokens
-            .delimited_by(just('{'), just('}'))
-            .map(Token::CurlyBraces)
+        let parenthesised = delimited('(', ')').map(Token::Parentheses).boxed();
+
+        let curly_braces = delimited('{', '}').map(Token::CurlyBraces).boxed();
+
+        let brackets = delimited('[', ']').map(Token::Brackets).boxed();
+
+        let unknown = any()
+            .filter(|c: &char| !c.is_whitespace() && !matches!(c, ')' | '}' | ']'))
+            .validate(|c, e, emitter| {
+                emitter.emit(SyntaxError::Invalid(Error::UnexpectedCharacter {
+                    character: c,
+                    span: e.span(),
+                }));
+
+                Token::Error
+            })
             .boxed();
 
         let comment = just("//")
@@ -83,14 +98,282 @@ pub fn lexer<'src>(
             .padded()
             .boxed();
 
-        let token = choice((simple, parenthesised, curly_braces))
+        let token = choice((simple, parenthesised, curly_braces, brackets, unknown))
             .map_with(|token, e| (token, e.span()))
             .padded_by(comment.repeated())
             .padded()
             .boxed();
 
-        token.repeated().collect().padded().boxed()
-    })
-    .then_ignore(end())
+        token
+            .repeated()
+            .collect::<Vec<_>>()
+            .map(split_plus_minus)
+            .padded()
+            .boxed()
+    });
+
+    let unmatched = one_of(")}]")
+        .validate(|c, e, emitter| {
+            emitter.emit(SyntaxError::Invalid(Error::UnmatchedDelimiter {
+                delimiter: c,
+                span: e.span(),
+            }));
+        })
+        .boxed();
+
+    tokens
+        .clone()
+        .foldl(unmatched.ignore_then(tokens).repeated(), |mut lhs, rhs| {
+            lhs.extend(rhs);
+            lhs
+        })
+        .then_ignore(end())
+        .boxed()
+}
+
+/// `+-` is the uncertainty operator only with whitespace on both sides, like `5 +- 0.1`, so
+/// `5+-3` is still read as `5 + -3`.
+fn split_plus_minus(tokens: Vec<Spanned<Token<'_>>>) -> Vec<Spanned<Token<'_>>> {
+    let spaced = |lhs: Option<&Spanned<Token>>, rhs: Option<&Spanned<Token>>| matches!((lhs, rhs), (Some((_, lhs)), Some((_, rhs))) if lhs.range().end < rhs.range().start);
+
+    let split = (0..tokens.len())
+        .map(|index| {
+            tokens[index].0 == Token::Simple(Simple::Punc(Punc::PlusMinusAscii))
+                && !(spaced(
+                    index.checked_sub(1).and_then(|index| tokens.get(index)),
+                    tokens.get(index),
+                ) && spaced(tokens.get(index), tokens.get(index + 1)))
+        })
+        .collect::<Vec<_>>();
+
+    tokens
+        .into_iter()
+        .zip(split)
+        .flat_map(|((token, span), split)| {
+            if split {
+                let start = span.range().start;
+
+                vec![
+                    (
+                        Token::Simple(Simple::Punc(Punc::Plus)),
+                        Span::new(span.context(), start..start + 1),
+                    ),
+                    (
+                        Token::Simple(Simple::Punc(Punc::Minus)),
+                        Span::new(span.context(), start + 1..span.range().end),
+                    ),
+                ]
+            } else {
+                vec![(token, span)]
+            }
+        })
+        .collect()
+}
+
+fn punctuation<'src>() -> impl Parser<'src, ParserInput<'src>, Simple<'src>, ParserExtra<'src>> {
+    choice((
+        just("<<").to(Punc::LessLess),
+        just(">>").to(Punc::GreaterGreater),
+        just("+-").to(Punc::PlusMinusAscii),
+        just("==").to(Punc::EqualsEquals),
+        just("!=").to(Punc::BangEquals),
+        just("<=").to(Punc::LessEquals),
+        just(">=").to(Punc::GreaterEquals),
+        just("..").to(Punc::DotDot),
+        just('<').to(Punc::Less),
+        just('>').to(Punc::Greater),
+        just('+').to(Punc::Plus),
+        just('-').to(Punc::Minus),
+        just('*').to(Punc::Star),
+        just('/').to(Punc::Slash),
+        just('=').to(Punc::Equals),
+        just('%').to(Punc::Percent),
+        just('&').to(Punc::Ampersand),
+        just('|').to(Punc::Pipe),
+        just('~').to(Punc::Tilde),
+        just(',').to(Punc::Comma),
+        just('±').to(Punc::PlusMinus),
+        just('?').to(Punc::Question),
+        just(':').to(Punc::Colon),
+        just('!').to(Punc::Bang),
+    ))
+    .map(Simple::Punc)
+    .boxed()
+}
+
+/// An ISO 8601 date like `2026-10-17`, or a date and time like `2026-10-17T09:30Z`, which is lexed
+/// before numbers so it is not read as a subtraction. A time zone in brackets like
+/// `2026-10-17T09:30[Europe/Berlin]` makes it a local time in that zone.
+fn date_time<'src>() -> impl Parser<'src, ParserInput<'src>, Simple<'src>, ParserExtra<'src>> {
+    let digits = |count| any().filter(char::is_ascii_digit).repeated().exactly(count);
+
+    let date = digits(4)
+        .then(just('-'))
+        .then(digits(2))
+        .then(just('-'))
+        .then(digits(2));
+
+    let seconds = just(':').then(digits(2)).then(
+        just('.')
+            .then(any().filter(char::is_ascii_digit).repeated().at_least(1))
+            .or_not(),
+    );
+
+    let offset = choice((
+        just('Z').ignored(),
+        one_of("+-")
+            .then(digits(2))
+            .then(just(':'))
+            .then(digits(2))
+            .ignored(),
+    ));
+
+    let time = just('T')
+        .then(digits(2))
+        .then(just(':'))
+        .then(digits(2))
+        .then(seconds.or_not())
+        .then(offset.or_not());
+
+    let zone = any()
+        .filter(|c: &char| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'))
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .delimited_by(just('['), just(']'));
+
+    date.then(time.or_not())
+        .to_slice()
+        .then(zone.or_not())
+        .validate(|(text, zone): (&str, _), e, emitter| {
+            let has_offset = text
+                .split_once('T')
+                .is_some_and(|(_, time)| time.contains(['Z', '+', '-']));
+
+            if zone.is_some() && has_offset {
+                emitter.emit(SyntaxError::Invalid(Error::OffsetAndZone {
+                    text: text.into(),
+                    span: e.span(),
+                }));
+            }
+
+            let date_time = DateTime::parse(text).unwrap_or_else(|| {
+                emitter.emit(SyntaxError::Invalid(Error::InvalidDate {
+                    text: text.into(),
+                    span: e.span(),
+                }));
+
+                DateTime::default()
+            });
+
+            Simple::DateTime { date_time, zone }
+        })
+        .boxed()
+}
+
+/// A string in double quotes like `"America/New_York"`, which cannot contain a double quote.
+fn string<'src>() -> impl Parser<'src, ParserInput<'src>, Simple<'src>, ParserExtra<'src>> {
+    just('"')
+        .ignore_then(none_of("\"\n").repeated().to_slice())
+        .then(just('"').or_not())
+        .validate(|(string, close), e, emitter| {
+            if close.is_none() {
+                emitter.emit(SyntaxError::Invalid(Error::UnclosedString(e.span())));
+            }
+
+            Simple::String(string)
+        })
+        .boxed()
+}
+
+fn number<'src>() -> impl Parser<'src, ParserInput<'src>, Simple<'src>, ParserExtra<'src>> {
+    let digits = |radix: u32| {
+        any()
+            .filter(move |c: &char| c.is_digit(radix))
+            .then(
+                any()
+                    .filter(move |c: &char| c.is_digit(radix) || *c == '_')
+                    .repeated(),
+            )
+            .to_slice()
+            .validate(|digits: &str, e, emitter| {
+                if digits.ends_with('_') || digits.contains("__") {
+                    emitter.emit(SyntaxError::Invalid(Error::MisplacedSeparator {
+                        digits: digits.into(),
+                        span: e.span(),
+                    }));
+                }
+
+                digits
+            })
+            .boxed()
+    };
+
+    let number_base = move |radix: Radix| {
+        let exponent = one_of(match radix {
+            Radix::Decimal => "eE",
+            Radix::Binary | Radix::Octal | Radix::Hexadecimal => "pP",
+        })
+        .ignore_then(one_of("+-").or_not().then(digits(10)).to_slice())
+        .validate(|exponent: &str, e, emitter| {
+            if exponent
+                .replace('_', "")
+                .parse::<i64>()
+                .map_or(true, |exponent| exponent.unsigned_abs() > MAX_EXPONENT)
+            {
+                emitter.emit(SyntaxError::Invalid(Error::ExponentOutOfRange {
+                    exponent: exponent.into(),
+                    span: e.span(),
+                }));
+            }
+
+            exponent
+        });
+
+        let repeating = digits(radix.to_u32()).delimited_by(just('('), just(')'));
+
+        let fraction = just('.')
+            .ignore_then(choice((
+                digits(radix.to_u32())
+                    .or_not()
+                    .to_slice()
+                    .then(repeating.map(Some)),
+                digits(radix.to_u32())
+                    .then_ignore(just("..."))
+                    .map(|fraction: &str| {
+                        let (after, repeating) =
+                            fraction.rsplit_once('_').unwrap_or(("", fraction));
+
+                        (after, Some(repeating))
+                    }),
+                digits(radix.to_u32()).map(|after| (after, None)),
+            )))
+            .boxed();
+
+        digits(radix.to_u32())
+            .then(fraction.or_not())
+            .then(exponent.or_not())
+            .map(move |((before, fraction), exponent)| Simple::Number {
+                before,
+                after: fraction.map(|(after, _)| after),
+                repeating: fraction.and_then(|(_, repeating)| repeating),
+                exponent,
+                radix,
+            })
+            .boxed()
+    };
+
+    let binary_number = just("0b").ignore_then(number_base(Radix::Binary));
+    let octal_number = just("0o").ignore_then(number_base(Radix::Octal));
+    let hexadecimal_number = just("0x").ignore_then(number_base(Radix::Hexadecimal));
+
+    let decimal_number = number_base(Radix::Decimal);
+
+    choice((
+        binary_number,
+        octal_number,
+        hexadecimal_number,
+        decimal_number,
+    ))
     .boxed()
 }
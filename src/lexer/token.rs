@@ -1,12 +1,14 @@
-use crate::span::Span;
+use crate::{span::Span, time::DateTime};
 
 pub type Spanned<T> = (T, Span);
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Token<'src> {
     Simple(Simple<'src>),
-    Parentheses(Vec<Spanned<Token<'src>>>),
-    CurlyBraces(Vec<Spanned<Token<'src>>>),
+    Parentheses(Vec<Spanned<Self>>),
+    CurlyBraces(Vec<Spanned<Self>>),
+    Brackets(Vec<Spanned<Self>>),
+    Error,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -15,8 +17,17 @@ pub enum Simple<'src> {
     Number {
         before: &'src str,
         after: Option<&'src str>,
+        repeating: Option<&'src str>,
+        exponent: Option<&'src str>,
         radix: Radix,
     },
+    /// A date and time, which is a local time if it is followed by a time zone like
+    /// `[America/New_York]`.
+    DateTime {
+        date_time: DateTime,
+        zone: Option<&'src str>,
+    },
+    String(&'src str),
     Kw(Kw),
     Punc(Punc),
 }
@@ -30,7 +41,7 @@ pub enum Radix {
 }
 
 impl Radix {
-    pub const fn to_u32(self) -> u32 {
+    pub const fn to_u8(self) -> u8 {
         match self {
             Self::Binary => 2,
             Self::Octal => 8,
@@ -38,6 +49,24 @@ impl Radix {
             Self::Hexadecimal => 16,
         }
     }
+
+    pub const fn to_u32(self) -> u32 {
+        self.to_u8() as u32
+    }
+
+    pub const fn exponent_marker(self) -> char {
+        match self {
+            Self::Decimal => 'e',
+            Self::Binary | Self::Octal | Self::Hexadecimal => 'p',
+        }
+    }
+
+    pub const fn exponent_base(self) -> u32 {
+        match self {
+            Self::Decimal => 10,
+            Self::Binary | Self::Octal | Self::Hexadecimal => 2,
+        }
+    }
 }
 
 impl core::fmt::Display for Radix {
@@ -58,11 +87,28 @@ impl core::fmt::Display for Radix {
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Kw {
     To,
+    In,
     Precision,
     FullPrecision,
     Help,
     Exit,
     Vars,
+    Explain,
+    Warnings,
+    Width,
+    Mod,
+    Div,
+    Xor,
+    As,
+    Of,
+    And,
+    Or,
+    Not,
+    True,
+    False,
+    If,
+    Then,
+    Else,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -72,6 +118,25 @@ pub enum Punc {
     Star,
     Slash,
     Equals,
+    Percent,
+    Ampersand,
+    Pipe,
+    Tilde,
+    LessLess,
+    GreaterGreater,
+    Comma,
+    PlusMinus,
+    PlusMinusAscii,
+    EqualsEquals,
+    BangEquals,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+    Question,
+    Colon,
+    DotDot,
+    Bang,
 }
 
 impl core::fmt::Display for Token<'_> {
@@ -80,6 +145,8 @@ impl core::fmt::Display for Token<'_> {
             Self::Simple(simple) => write!(f, "{simple}"),
             Self::Parentheses(_tokens) => write!(f, "(...)"),
             Self::CurlyBraces(_tokens) => write!(f, "{{...}}"),
+            Self::Brackets(_tokens) => write!(f, "[...]"),
+            Self::Error => write!(f, "<error>"),
         }
     }
 }
@@ -91,8 +158,23 @@ impl core::fmt::Display for Simple<'_> {
             Self::Number {
                 before,
                 after,
+                repeating,
+                exponent,
                 radix,
-            } => write!(f, "{}", number_to_string(before, after.as_deref(), *radix)),
+            } => write!(
+                f,
+                "{}",
+                number_to_string(before, *after, *repeating, *exponent, *radix)
+            ),
+            Self::DateTime {
+                date_time,
+                zone: None,
+            } => write!(f, "{}", date_time.display_iso()),
+            Self::DateTime {
+                date_time,
+                zone: Some(zone),
+            } => write!(f, "{}[{zone}]", date_time.display_iso()),
+            Self::String(string) => write!(f, "\"{string}\""),
             Self::Kw(kw) => write!(f, "{kw}"),
             Self::Punc(punc) => write!(f, "{punc}"),
         }
@@ -106,11 +188,28 @@ impl core::fmt::Display for Kw {
             "{}",
             match self {
                 Self::To => "to",
+                Self::In => "in",
                 Self::Precision => "precision",
                 Self::FullPrecision => "fullprecision",
                 Self::Help => "help",
                 Self::Exit => "exit",
                 Self::Vars => "vars",
+                Self::Explain => "explain",
+                Self::Warnings => "warnings",
+                Self::Width => "width",
+                Self::Mod => "mod",
+                Self::Div => "div",
+                Self::Xor => "xor",
+                Self::As => "as",
+                Self::Of => "of",
+                Self::And => "and",
+                Self::Or => "or",
+                Self::Not => "not",
+                Self::True => "true",
+                Self::False => "false",
+                Self::If => "if",
+                Self::Then => "then",
+                Self::Else => "else",
             }
         )
     }
@@ -127,16 +226,46 @@ impl core::fmt::Display for Punc {
                 Self::Star => "*",
                 Self::Slash => "/",
                 Self::Equals => "=",
+                Self::Percent => "%",
+                Self::Ampersand => "&",
+                Self::Pipe => "|",
+                Self::Tilde => "~",
+                Self::LessLess => "<<",
+                Self::GreaterGreater => ">>",
+                Self::Comma => ",",
+                Self::PlusMinus => "±",
+                Self::PlusMinusAscii => "+-",
+                Self::EqualsEquals => "==",
+                Self::BangEquals => "!=",
+                Self::Less => "<",
+                Self::LessEquals => "<=",
+                Self::Greater => ">",
+                Self::GreaterEquals => ">=",
+                Self::Question => "?",
+                Self::Colon => ":",
+                Self::DotDot => "..",
+                Self::Bang => "!",
             }
         )
     }
 }
 
-fn number_to_string(before: &str, after: Option<&str>, radix: Radix) -> String {
+fn number_to_string(
+    before: &str,
+    after: Option<&str>,
+    repeating: Option<&str>,
+    exponent: Option<&str>,
+    radix: Radix,
+) -> String {
     format!(
-        "{}{}{}",
+        "{}{}{}{}{}",
         radix,
         before,
-        after.map_or_else(String::new, |after| format!(".{after}"))
+        after.map_or_else(String::new, |after| format!(".{after}")),
+        repeating.map_or_else(String::new, |repeating| format!("({repeating})")),
+        exponent.map_or_else(String::new, |exponent| format!(
+            "{}{exponent}",
+            radix.exponent_marker()
+        ))
     )
 }
@@ -7,23 +7,113 @@ use codespan_reporting::{
     },
 };
 use core::ops::ControlFlow;
-use diagnostics::{error::convert, report::report};
-use evaluator::Evaluator;
+use currency::Rates;
+use diagnostics::{
+    error::convert,
+    report::{json, report},
+    Diag,
+};
+use evaluator::{Evaluator, Value};
+use json::Json;
 use lasso::ThreadedRodeo;
+use malachite::Rational;
 use owo_colors::{AnsiColors, OwoColorize as _};
 use rustyline::error::ReadlineError;
 use span::{File, FileId, Span};
 use std::sync::LazyLock;
+use time::DateTime;
 
+mod currency;
+mod data;
 mod diagnostics;
 mod evaluator;
+mod json;
 mod lexer;
 mod parser;
 mod span;
+#[cfg(test)]
+mod tests;
+mod time;
 
 static RODEO: LazyLock<ThreadedRodeo> = LazyLock::new(ThreadedRodeo::new);
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl core::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown format `{s}`, expected `text` or `json`")),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    format: Format,
+    /// A fixed time for `now` and `today`, so results do not depend on when they are computed.
+    now: Option<DateTime>,
+    /// Exchange rates for converting between currencies, read from a file.
+    rates: Option<Rates>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+
+    let parse_now = |value: &str| {
+        DateTime::parse(value).ok_or_else(|| format!("Invalid date `{value}` for `--now`"))
+    };
+
+    let read_rates = |path: &str| {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("Could not read `{path}` for `--rates`: {err}"))?;
+
+        Rates::parse(&text).map_err(|err| format!("Invalid rates in `{path}`: {err}"))
+    };
+
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            parsed.format = args
+                .next()
+                .ok_or_else(|| "Missing value for `--format`".to_string())?
+                .parse()?;
+        } else if let Some(value) = arg.strip_prefix("--format=") {
+            parsed.format = value.parse()?;
+        } else if arg == "--now" {
+            parsed.now = Some(parse_now(
+                &args
+                    .next()
+                    .ok_or_else(|| "Missing value for `--now`".to_string())?,
+            )?);
+        } else if let Some(value) = arg.strip_prefix("--now=") {
+            parsed.now = Some(parse_now(value)?);
+        } else if arg == "--rates" {
+            parsed.rates = Some(read_rates(
+                &args
+                    .next()
+                    .ok_or_else(|| "Missing value for `--rates`".to_string())?,
+            )?);
+        } else if let Some(value) = arg.strip_prefix("--rates=") {
+            parsed.rates = Some(read_rates(value)?);
+        } else {
+            return Err(format!("Unknown argument `{arg}`"));
+        }
+    }
+
+    Ok(parsed)
+}
+
 fn main() -> Result<(), Box<dyn core::error::Error>> {
+    let Args { format, now, rates } = parse_args(std::env::args().skip(1))?;
+
     let mut editor = rustyline::DefaultEditor::new()?;
 
     let mut files = SimpleFiles::new();
@@ -35,7 +125,21 @@ fn main() -> Result<(), Box<dyn core::error::Error>> {
         ("tau", core::f64::consts::TAU),
         ("e", core::f64::consts::E),
     ] {
-        evaluator.insert(name, value);
+        evaluator.insert(name, Value::Number(Rational::try_from(value).unwrap()));
+    }
+
+    evaluator.insert("i", Value::I);
+
+    if let Some(now) = now {
+        evaluator.set_clock(now);
+    }
+
+    if let Some(rates) = rates {
+        evaluator.set_rates(rates);
+    }
+
+    if format == Format::Json {
+        evaluator.set_plain();
     }
 
     eprintln!("Welcome to Polar v{}!", env!("CARGO_PKG_VERSION"));
@@ -59,7 +163,7 @@ fn main() -> Result<(), Box<dyn core::error::Error>> {
 
                 let file_id = FileId::new(files.add("<stdin>", input.clone()));
 
-                match handle_input(&mut evaluator, &files, &input, File::Repl(file_id))? {
+                match handle_input(&mut evaluator, &files, &input, File::Repl(file_id), format)? {
                     ControlFlow::Continue(success) => previous_success = success,
                     ControlFlow::Break(()) => break,
                 }
@@ -85,6 +189,7 @@ fn handle_input(
     files: &SimpleFiles<&str, String>,
     input: &str,
     file_id: File,
+    format: Format,
 ) -> Result<ControlFlow<(), bool>, Box<dyn core::error::Error>> {
     let mut errors = vec![];
 
@@ -94,7 +199,7 @@ fn handle_input(
 
     errors.extend(lexer_errors.iter().flat_map(|error| convert(error)));
 
-    let (statement, parser_errors) = tokens.as_ref().map_or_else(
+    let (statement, parser_errors) = tokens.as_ref().filter(|_| errors.is_empty()).map_or_else(
         || (None, vec![]),
         |tokens| {
             let eoi = tokens
@@ -109,22 +214,71 @@ fn handle_input(
 
     errors.extend(parser_errors.iter().flat_map(|error| convert(error)));
 
-    if let Some(statement) = statement {
+    let mut value = None;
+
+    if let Some(statement) = statement.filter(|_| errors.is_empty()) {
         match evaluator.evaluate_statement(statement) {
-            Ok(ControlFlow::Continue(Some(value))) => {
-                println!("{}", value.display(evaluator.options()));
-            }
-            Ok(ControlFlow::Continue(None)) => {}
+            Ok(ControlFlow::Continue(result)) => value = result,
             Ok(ControlFlow::Break(())) => return Ok(ControlFlow::Break(())),
             Err(err) => errors.push(err),
         }
     }
 
+    let warnings = evaluator.take_warnings();
+    let output = evaluator.take_output();
+
+    let diagnostics = errors
+        .iter()
+        .map(|error| error as &dyn Diag)
+        .chain(warnings.iter().map(|warning| warning as &dyn Diag))
+        .collect::<Vec<_>>();
+
+    if format == Format::Json {
+        println!(
+            "{}",
+            Json::Object(vec![
+                ("input", Json::string(input)),
+                (
+                    "output",
+                    if output.is_empty() {
+                        Json::Null
+                    } else {
+                        Json::String(output)
+                    },
+                ),
+                (
+                    "value",
+                    value.as_ref().map_or(Json::Null, |output| {
+                        output.value.to_json(evaluator.options())
+                    }),
+                ),
+                (
+                    "display",
+                    value.as_ref().map_or(Json::Null, |output| {
+                        Json::String(output.display(evaluator.options()))
+                    }),
+                ),
+                (
+                    "diagnostics",
+                    Json::Array(diagnostics.into_iter().map(json).collect()),
+                ),
+            ])
+        );
+
+        return Ok(ControlFlow::Continue(errors.is_empty()));
+    }
+
+    print!("{output}");
+
+    if let Some(value) = value {
+        println!("{}", value.display(evaluator.options()));
+    }
+
     let writer = StandardStream::stderr(ColorChoice::Auto);
     let term_config = term::Config::default();
 
-    for error in &errors {
-        let diagnostic = report(error);
+    for diagnostic in diagnostics {
+        let diagnostic = report(diagnostic);
 
         term::emit(&mut writer.lock(), &term_config, files, &diagnostic)?;
     }
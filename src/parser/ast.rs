@@ -1,34 +1,89 @@
-use crate::{span::Spanned, RODEO};
+use crate::{
+    currency::Currency,
+    data::{DataUnit, RateUnit},
+    lexer::token::Radix,
+    span::Spanned,
+    time::DateTime,
+    time::TimeUnit,
+    RODEO,
+};
 use lasso::Spur;
 use malachite::Rational;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Statement {
-    Expression(Spanned<Expression>),
+    Expression {
+        expr: Spanned<Expression>,
+        target: Option<Spanned<Target>>,
+    },
     Assign {
         name: Spanned<Identifier>,
         value: Spanned<Expression>,
     },
     SetPrecision(Spanned<Expression>),
     FullPrecision,
+    SetWarnings(bool),
+    SetWidth(Option<IntegerWidth>),
     Help,
     Exit,
     Vars,
+    Explain(Spanned<Identifier>),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Expression {
     Number(Rational),
+    DateTime(DateTime),
+    /// A local time in a time zone, counted as if it were in UTC.
+    ZonedDateTime {
+        local: DateTime,
+        zone: Spanned<Identifier>,
+    },
+    Bool(bool),
     Variable(Spanned<Identifier>),
+    List(Vec<Spanned<Self>>),
+    Index {
+        list: Spanned<Box<Self>>,
+        index: Spanned<Box<Self>>,
+    },
+    Call {
+        name: Spanned<Identifier>,
+        args: Vec<Spanned<Self>>,
+    },
     BinaryOp {
         op: Spanned<BinaryOp>,
-        lhs: Spanned<Box<Expression>>,
-        rhs: Spanned<Box<Expression>>,
+        lhs: Spanned<Box<Self>>,
+        rhs: Spanned<Box<Self>>,
     },
     UnaryOp {
         op: Spanned<UnaryOp>,
-        expr: Spanned<Box<Expression>>,
+        expr: Spanned<Box<Self>>,
+    },
+    If {
+        condition: Spanned<Box<Self>>,
+        then: Spanned<Box<Self>>,
+        otherwise: Spanned<Box<Self>>,
     },
+    Error,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Target {
+    Radix(Radix),
+    Base(Spanned<Expression>),
+    Repeating,
+    Fraction,
+    Mixed,
+    Percent,
+    Polar,
+    Concise,
+    ContinuedFraction,
+    Time(TimeUnit),
+    Iso,
+    TimeZone(Spanned<Identifier>),
+    Currency(Currency),
+    Data(DataUnit),
+    DataRate(RateUnit),
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -37,6 +92,25 @@ pub enum BinaryOp {
     Sub,
     Mul,
     Div,
+    Mod,
+    IntDiv,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    PercentOf,
+    PlusMinus,
+    Uncertainty,
+    Range,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
 }
 
 impl core::fmt::Display for BinaryOp {
@@ -46,6 +120,25 @@ impl core::fmt::Display for BinaryOp {
             Self::Sub => write!(f, "-"),
             Self::Mul => write!(f, "*"),
             Self::Div => write!(f, "/"),
+            Self::Mod => write!(f, "mod"),
+            Self::IntDiv => write!(f, "div"),
+            Self::BitAnd => write!(f, "&"),
+            Self::BitOr => write!(f, "|"),
+            Self::BitXor => write!(f, "xor"),
+            Self::Shl => write!(f, "<<"),
+            Self::Shr => write!(f, ">>"),
+            Self::PercentOf => write!(f, "as % of"),
+            Self::PlusMinus => write!(f, "±"),
+            Self::Uncertainty => write!(f, "+-"),
+            Self::Range => write!(f, ".."),
+            Self::Eq => write!(f, "=="),
+            Self::Ne => write!(f, "!="),
+            Self::Lt => write!(f, "<"),
+            Self::Le => write!(f, "<="),
+            Self::Gt => write!(f, ">"),
+            Self::Ge => write!(f, ">="),
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
         }
     }
 }
@@ -53,16 +146,61 @@ impl core::fmt::Display for BinaryOp {
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum UnaryOp {
     Neg,
+    Not,
+    Percent,
+    Factorial,
+    LogicalNot,
+    Time(TimeUnit),
+    Currency(Currency),
+    Data(DataUnit),
+    DataRate(RateUnit),
 }
 
 impl core::fmt::Display for UnaryOp {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Neg => write!(f, "-"),
+            Self::Not => write!(f, "~"),
+            Self::Percent => write!(f, "%"),
+            Self::Factorial => write!(f, "!"),
+            Self::LogicalNot => write!(f, "not"),
+            Self::Time(unit) => write!(f, "{unit}"),
+            Self::Currency(currency) => write!(f, "{currency}"),
+            Self::Data(unit) => write!(f, "{unit}"),
+            Self::DataRate(unit) => write!(f, "{unit}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IntegerWidth {
+    pub signed: bool,
+    pub bits: u64,
+}
+
+impl core::str::FromStr for IntegerWidth {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (signed, bits) = match s.split_at_checked(1) {
+            Some(("u", bits)) => (false, bits),
+            Some(("i", bits)) => (true, bits),
+            _ => return Err(()),
+        };
+
+        match bits.parse() {
+            Ok(bits @ (8 | 16 | 32 | 64 | 128)) => Ok(Self { signed, bits }),
+            _ => Err(()),
         }
     }
 }
 
+impl core::fmt::Display for IntegerWidth {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", if self.signed { "i" } else { "u" }, self.bits)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Identifier(Spanned<Spur>);
 
@@ -1,17 +1,28 @@
 use crate::{
-    lexer::token::{Kw, Punc, Simple, Token},
+    currency::Currency,
+    data::{DataUnit, RateUnit},
+    diagnostics::{
+        error::{Error, SyntaxError},
+        suggest::{closest, units},
+    },
+    lexer::token::{Kw, Punc, Radix, Simple, Token},
     span::{Span, Spanned},
+    time::TimeUnit,
     RODEO,
 };
-use ast::{BinaryOp, Expression, Identifier, Statement, UnaryOp};
-use chumsky::{extra, input::SpannedInput, prelude::*};
-use malachite::{rational_sequences::RationalSequence, Natural, Rational};
+use ast::{BinaryOp, Expression, Identifier, Statement, Target, UnaryOp};
+use chumsky::{extra, input::SpannedInput, prelude::*, util::MaybeRef};
+use malachite::{
+    num::{arithmetic::traits::Pow, basic::traits::One},
+    rational_sequences::RationalSequence,
+    Natural, Rational,
+};
 
 pub mod ast;
 
 type ParserInput<'src, 'tok> = SpannedInput<Token<'src>, Span, &'tok [(Token<'src>, Span)]>;
 
-type ParserExtra<'src, 'tok> = extra::Err<Rich<'tok, Token<'src>, Span, &'src str>>;
+type ParserExtra<'src, 'tok> = extra::Err<SyntaxError<'tok, Token<'src>, &'src str>>;
 
 pub fn repl<'src: 'tok, 'tok>(
 ) -> impl Parser<'tok, ParserInput<'src, 'tok>, Statement, ParserExtra<'src, 'tok>> {
@@ -20,7 +31,18 @@ pub fn repl<'src: 'tok, 'tok>(
 
 fn statement<'src: 'tok, 'tok>(
 ) -> impl Parser<'tok, ParserInput<'src, 'tok>, Statement, ParserExtra<'src, 'tok>> {
-    let expr = expression().with_span().map(Statement::Expression).boxed();
+    let expr = expression()
+        .with_span()
+        .then(
+            choice((
+                just(Token::Simple(Simple::Kw(Kw::To))).ignore_then(target().with_span()),
+                just(Token::Simple(Simple::Kw(Kw::In)))
+                    .ignore_then(zone().map(Target::TimeZone).with_span()),
+            ))
+            .or_not(),
+        )
+        .map(|(expr, target)| Statement::Expression { expr, target })
+        .boxed();
 
     let assign = ident()
         .with_span()
@@ -54,6 +76,35 @@ fn statement<'src: 'tok, 'tok>(
         .map(|()| Statement::Vars)
         .boxed();
 
+    let explain = just(Token::Simple(Simple::Kw(Kw::Explain)))
+        .ignore_then(ident().labelled("an error code").with_span())
+        .map(Statement::Explain)
+        .boxed();
+
+    let set_width = just(Token::Simple(Simple::Kw(Kw::Width)))
+        .ignore_then(
+            select_token(|token, _| match token {
+                Token::Simple(Simple::Identifier("off")) => Some(None),
+                Token::Simple(Simple::Identifier(ident)) => ident.parse().ok().map(Some),
+                _ => None,
+            })
+            .labelled("an integer type or `off`"),
+        )
+        .map(Statement::SetWidth)
+        .boxed();
+
+    let set_warnings = just(Token::Simple(Simple::Kw(Kw::Warnings)))
+        .ignore_then(
+            select_token(|token, _| match token {
+                Token::Simple(Simple::Identifier("on")) => Some(true),
+                Token::Simple(Simple::Identifier("off")) => Some(false),
+                _ => None,
+            })
+            .labelled("`on` or `off`"),
+        )
+        .map(Statement::SetWarnings)
+        .boxed();
+
     choice((
         assign,
         expr,
@@ -62,106 +113,548 @@ fn statement<'src: 'tok, 'tok>(
         help,
         exit,
         vars,
+        explain,
+        set_warnings,
+        set_width,
     ))
+    .labelled("an expression or a command")
     .boxed()
 }
 
-fn expression<'src: 'tok, 'tok>(
-) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> {
-    macro_rules! unary_op {
-        ($base:expr, $(($punc:expr => $to:expr)),*) => {{
-            let ops = choice((
-                $(
-                    just(Token::Simple(Simple::Punc($punc))).to($to),
-                )*
-            ))
-            .with_span()
-            .boxed();
+macro_rules! unary_op {
+    ($base:expr, $(($simple:expr => $to:expr)),*) => {{
+        let ops = choice((
+            $(
+                just(Token::Simple($simple)).to($to),
+            )*
+        ))
+        .labelled("an expression")
+        .with_span()
+        .boxed();
 
-            ops
-                .repeated()
-                .foldr($base.with_span(), |op, expr| {
-                    let span = op.1.union(expr.1);
-
-                    Spanned::new(
-                        Expression::UnaryOp {
-                            op,
-                            expr: expr.boxed(),
-                        },
-                        span
-                    )
-                })
-                .map(|expr| expr.0)
-                .boxed()
-        }};
-    }
+        ops
+            .repeated()
+            .foldr($base.with_span(), |op, expr| {
+                let span = op.1.union(expr.1);
+
+                Spanned::new(
+                    Expression::UnaryOp {
+                        op,
+                        expr: expr.boxed(),
+                    },
+                    span
+                )
+            })
+            .map(|expr| expr.0)
+            .boxed()
+    }};
+}
 
-    macro_rules! binary_op {
-        ($base:expr, $(($punc:expr => $to:expr)),*) => {{
-            let ops = choice((
+macro_rules! binary_op {
+    ($base:expr, $(($simple:expr => $to:expr)),*) => {
+        binary_op!(
+            @ops $base,
+            choice((
                 $(
-                    just(Token::Simple(Simple::Punc($punc))).to($to),
+                    just(Token::Simple($simple)).to($to),
                 )*
             ))
-            .with_span()
-            .boxed();
+        )
+    };
+    (@ops $base:expr, $ops:expr) => {{
+        let ops = $ops.labelled("an operator").with_span().boxed();
 
-            $base
-                .clone()
-                .with_span()
-                .foldl(ops.then($base.with_span()).repeated(), |lhs, (op, rhs)| {
-                    let span = lhs.1.union(rhs.1);
-
-                    Spanned::new(
-                        Expression::BinaryOp {
-                            op,
-                            lhs: lhs.boxed(),
-                            rhs: rhs.boxed(),
-                        },
-                        span
-                    )
-                })
-                .map(|expr| expr.0)
-                .boxed()
-        }};
-    }
+        $base
+            .clone()
+            .with_span()
+            .foldl(ops.then($base.with_span()).repeated(), |lhs, (op, rhs)| {
+                let span = lhs.1.union(rhs.1);
+
+                Spanned::new(
+                    Expression::BinaryOp {
+                        op,
+                        lhs: lhs.boxed(),
+                        rhs: rhs.boxed(),
+                    },
+                    span
+                )
+            })
+            .map(|expr| expr.0)
+            .boxed()
+    }};
+}
 
+fn expression<'src: 'tok, 'tok>(
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> {
     recursive(|expression| {
-        let number = select! {
-            Token::Simple(Simple::Number{ before, after, radix }) => rational_from_str(before, after, radix.to_u32()),
-        }
-        .map(Expression::Number);
+        let literal = literal();
 
         let variable = ident().with_span().map(Expression::Variable).boxed();
 
+        let call = ident()
+            .with_span()
+            .then(
+                expression
+                    .clone()
+                    .with_span()
+                    .separated_by(just(Token::Simple(Simple::Punc(Punc::Comma))))
+                    .collect::<Vec<_>>()
+                    .parenthesized(),
+            )
+            .map(|(name, args)| Expression::Call { name, args })
+            .recover_with(via_parser(
+                ident()
+                    .then(select_token(|token, _| match token {
+                        Token::Parentheses(_) => Some(()),
+                        _ => None,
+                    }))
+                    .to(Expression::Error),
+            ))
+            .boxed();
+
         let parenthesized = expression
             .clone()
             .with_span()
             .parenthesized()
             .map(|expr| expr.0)
+            .recover_with(via_parser(select_token(|token, _| match token {
+                Token::Parentheses(_) => Some(Expression::Error),
+                _ => None,
+            })))
+            .boxed();
+
+        let list = expression
+            .clone()
+            .with_span()
+            .separated_by(just(Token::Simple(Simple::Punc(Punc::Comma))))
+            .allow_trailing()
+            .collect::<Vec<_>>()
+            .bracketed()
+            .map(Expression::List)
+            .recover_with(via_parser(select_token(|token, _| match token {
+                Token::Brackets(_) => Some(Expression::Error),
+                _ => None,
+            })))
             .boxed();
 
-        let atom = choice((parenthesized, number, variable)).boxed();
+        let atom = choice((parenthesized, list, call, literal, variable)).boxed();
+
+        let unary = unary_op!(
+            postfix(index(atom, expression.clone())),
+            (Simple::Punc(Punc::Minus) => UnaryOp::Neg),
+            (Simple::Punc(Punc::Tilde) => UnaryOp::Not)
+        )
+        .labelled("an expression")
+        .boxed();
+
+        let factor = binary_op!(
+            unary,
+            (Simple::Punc(Punc::Star) => BinaryOp::Mul),
+            (Simple::Punc(Punc::Slash) => BinaryOp::Div),
+            (Simple::Punc(Punc::Percent) => BinaryOp::Mod),
+            (Simple::Kw(Kw::Mod) => BinaryOp::Mod),
+            (Simple::Kw(Kw::Div) => BinaryOp::IntDiv)
+        )
+        .boxed();
+
+        let term = binary_op!(
+            factor,
+            (Simple::Punc(Punc::Plus) => BinaryOp::Add),
+            (Simple::Punc(Punc::Minus) => BinaryOp::Sub),
+            (Simple::Punc(Punc::PlusMinus) => BinaryOp::PlusMinus),
+            (Simple::Punc(Punc::PlusMinusAscii) => BinaryOp::Uncertainty)
+        )
+        .boxed();
+
+        let bitwise = bitwise(term);
+
+        let range = binary_op!(bitwise, (Simple::Punc(Punc::DotDot) => BinaryOp::Range)).boxed();
+
+        let ratio = binary_op!(
+            @ops range,
+            just(Token::Simple(Simple::Kw(Kw::As)))
+                .then(just(Token::Simple(Simple::Punc(Punc::Percent))))
+                .then(just(Token::Simple(Simple::Kw(Kw::Of))))
+                .to(BinaryOp::PercentOf)
+        )
+        .boxed();
+
+        conditional(logical(ratio), expression).labelled("an expression")
+    })
+    .boxed()
+}
+
+fn literal<'src: 'tok, 'tok>(
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> {
+    select_token(|token, span| match token {
+        Token::Simple(Simple::Number {
+            before,
+            after,
+            repeating,
+            exponent,
+            radix,
+        }) => Some(Expression::Number(rational_from_str(
+            before, *after, *repeating, *exponent, *radix,
+        ))),
+        Token::Simple(Simple::DateTime {
+            date_time,
+            zone: None,
+        }) => Some(Expression::DateTime(date_time.clone())),
+        Token::Simple(Simple::DateTime {
+            date_time,
+            zone: Some(zone),
+        }) => Some(Expression::ZonedDateTime {
+            local: date_time.clone(),
+            zone: Spanned::new(
+                Identifier::new(Spanned::new(RODEO.get_or_intern(zone), span)),
+                span,
+            ),
+        }),
+        Token::Simple(Simple::Kw(Kw::True)) => Some(Expression::Bool(true)),
+        Token::Simple(Simple::Kw(Kw::False)) => Some(Expression::Bool(false)),
+        _ => None,
+    })
+    .boxed()
+}
+
+// Both forms of conditional bind looser than anything else, so each branch extends as far as it
+// can, and `a ? b : c ? d : e` nests to the right.
+fn conditional<'src: 'tok, 'tok>(
+    base: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + Clone + 'tok,
+    expression: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>>
+        + Clone
+        + 'tok,
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + Clone {
+    let if_then_else = just(Token::Simple(Simple::Kw(Kw::If)))
+        .ignore_then(expression.clone().with_span())
+        .then_ignore(just(Token::Simple(Simple::Kw(Kw::Then))))
+        .then(expression.clone().with_span())
+        .then_ignore(just(Token::Simple(Simple::Kw(Kw::Else))))
+        .then(expression.clone().with_span())
+        .map(|((condition, then), otherwise)| Expression::If {
+            condition: condition.boxed(),
+            then: then.boxed(),
+            otherwise: otherwise.boxed(),
+        })
+        .boxed();
+
+    let ternary = base
+        .with_span()
+        .then(
+            just(Token::Simple(Simple::Punc(Punc::Question)))
+                .labelled("an operator")
+                .ignore_then(expression.clone().with_span())
+                .then_ignore(just(Token::Simple(Simple::Punc(Punc::Colon))))
+                .then(expression.with_span())
+                .or_not(),
+        )
+        .map(|(condition, branches)| match branches {
+            Some((then, otherwise)) => Expression::If {
+                condition: condition.boxed(),
+                then: then.boxed(),
+                otherwise: otherwise.boxed(),
+            },
+            None => condition.0,
+        })
+        .boxed();
+
+    choice((if_then_else, ternary)).boxed()
+}
+
+fn bitwise<'src: 'tok, 'tok>(
+    base: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + Clone + 'tok,
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + Clone {
+    let shift = binary_op!(
+        base,
+        (Simple::Punc(Punc::LessLess) => BinaryOp::Shl),
+        (Simple::Punc(Punc::GreaterGreater) => BinaryOp::Shr)
+    )
+    .boxed();
+
+    let bit_and = binary_op!(shift, (Simple::Punc(Punc::Ampersand) => BinaryOp::BitAnd)).boxed();
+
+    let bit_xor = binary_op!(bit_and, (Simple::Kw(Kw::Xor) => BinaryOp::BitXor)).boxed();
+
+    binary_op!(bit_xor, (Simple::Punc(Punc::Pipe) => BinaryOp::BitOr)).boxed()
+}
+
+// Comparisons bind looser than every arithmetic operator, followed by `not`, `and` and `or`.
+fn logical<'src: 'tok, 'tok>(
+    base: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + Clone + 'tok,
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + Clone {
+    let comparison = binary_op!(
+        base,
+        (Simple::Punc(Punc::EqualsEquals) => BinaryOp::Eq),
+        (Simple::Punc(Punc::BangEquals) => BinaryOp::Ne),
+        (Simple::Punc(Punc::Less) => BinaryOp::Lt),
+        (Simple::Punc(Punc::LessEquals) => BinaryOp::Le),
+        (Simple::Punc(Punc::Greater) => BinaryOp::Gt),
+        (Simple::Punc(Punc::GreaterEquals) => BinaryOp::Ge)
+    )
+    .boxed();
+
+    let not = unary_op!(comparison, (Simple::Kw(Kw::Not) => UnaryOp::LogicalNot)).boxed();
+
+    let and = binary_op!(not, (Simple::Kw(Kw::And) => BinaryOp::And)).boxed();
 
-        let unary = unary_op!(atom, (Punc::Minus => UnaryOp::Neg)).boxed();
+    binary_op!(and, (Simple::Kw(Kw::Or) => BinaryOp::Or)).boxed()
+}
+
+fn index<'src: 'tok, 'tok>(
+    atom: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + 'tok,
+    expression: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + 'tok,
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> {
+    let index = expression.with_span().bracketed().with_span().boxed();
+
+    atom.with_span()
+        .foldl(index.repeated(), |list, index| {
+            let span = list.1.union(index.1);
+
+            Spanned::new(
+                Expression::Index {
+                    list: list.boxed(),
+                    index: index.0.boxed(),
+                },
+                span,
+            )
+        })
+        .map(|expr| expr.0)
+        .boxed()
+}
+
+// A `%` directly followed by another operand is the remainder operator, so `7 % 3` and
+// `200 + 15%` both parse the way they read. Nothing but an operand can follow a percentage and `~`,
+// `not` or `if`, so those always start a remainder, while `10 % -3` and `15% - 3` cannot be told
+// apart and are rejected.
+fn postfix<'src: 'tok, 'tok>(
+    atom: impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> + 'tok,
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Expression, ParserExtra<'src, 'tok>> {
+    let operand = select_token(|token, _| match token {
+        Token::Simple(
+            Simple::Number { .. }
+            | Simple::Identifier(_)
+            | Simple::Punc(Punc::Tilde)
+            | Simple::Kw(Kw::Not | Kw::If),
+        )
+        | Token::Parentheses(_)
+        | Token::Brackets(_) => Some(()),
+        _ => None,
+    });
+
+    let ambiguous = just(Token::Simple(Simple::Punc(Punc::Percent)))
+        .then_ignore(just(Token::Simple(Simple::Punc(Punc::Minus))).rewind())
+        .validate(|_, e, emitter| {
+            emitter.emit(SyntaxError::Invalid(Error::AmbiguousPercent(e.span())));
+
+            UnaryOp::Percent
+        });
+
+    let percent = just(Token::Simple(Simple::Punc(Punc::Percent)))
+        .then_ignore(operand.not())
+        .to(UnaryOp::Percent);
+
+    let factorial = just(Token::Simple(Simple::Punc(Punc::Bang))).to(UnaryOp::Factorial);
+
+    let unit = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier(name)) => TimeUnit::from_name(name)
+            .map(UnaryOp::Time)
+            .or_else(|| Currency::from_code(name).map(UnaryOp::Currency)),
+        _ => None,
+    });
+
+    let data = data_unit().map(|unit| match unit {
+        DataTarget::Data(unit) => UnaryOp::Data(unit),
+        DataTarget::Rate(unit) => UnaryOp::DataRate(unit),
+    });
+
+    let postfix = choice((ambiguous, percent, factorial, unit, data))
+        .labelled("an operator")
+        .with_span()
+        .boxed();
+
+    // Nothing else can follow an operand directly, so a name that is close to a unit is
+    // reported as a typo of it.
+    let unknown_unit = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier(name)) => {
+            unit_suggestion(name).map(|suggestion| (*name, suggestion))
+        }
+        _ => None,
+    })
+    .validate(|(name, suggestion), e, emitter| {
+        emitter.emit(SyntaxError::Invalid(Error::UnknownUnit {
+            name: name.into(),
+            suggestion,
+            span: e.span(),
+        }));
+    });
+
+    atom.with_span()
+        .foldl(postfix.repeated(), |expr, op| {
+            let span = expr.1.union(op.1);
+
+            Spanned::new(
+                Expression::UnaryOp {
+                    op,
+                    expr: expr.boxed(),
+                },
+                span,
+            )
+        })
+        .then(unknown_unit.or_not())
+        .map(|(expr, unknown)| match unknown {
+            Some(()) => Expression::Error,
+            None => expr.0,
+        })
+        .boxed()
+}
+
+/// What a name was probably meant to be, if it is close to the name of a unit.
+fn unit_suggestion(name: &str) -> Option<String> {
+    if name == "KB" {
+        return Some("write `kB` for 1000 bytes or `KiB` for 1024 bytes".into());
+    }
+
+    units()
+        .find(|unit| unit.eq_ignore_ascii_case(name))
+        .or_else(|| closest(name, units()))
+        .map(|unit| format!("did you mean `{unit}`?"))
+}
+
+fn target<'src: 'tok, 'tok>(
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Target, ParserExtra<'src, 'tok>> {
+    let radix = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier("bin")) => Some(Radix::Binary),
+        Token::Simple(Simple::Identifier("oct")) => Some(Radix::Octal),
+        Token::Simple(Simple::Identifier("dec")) => Some(Radix::Decimal),
+        Token::Simple(Simple::Identifier("hex")) => Some(Radix::Hexadecimal),
+        _ => None,
+    })
+    .map(Target::Radix)
+    .boxed();
+
+    let fraction = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier("repeating")) => Some(Target::Repeating),
+        Token::Simple(Simple::Identifier("fraction")) => Some(Target::Fraction),
+        Token::Simple(Simple::Identifier("mixed")) => Some(Target::Mixed),
+        Token::Simple(Simple::Identifier("polar")) => Some(Target::Polar),
+        Token::Simple(Simple::Identifier("concise")) => Some(Target::Concise),
+        Token::Simple(Simple::Identifier("cf")) => Some(Target::ContinuedFraction),
+        Token::Simple(Simple::Identifier("iso")) => Some(Target::Iso),
+        Token::Simple(Simple::Identifier(name)) => TimeUnit::from_name(name)
+            .map(Target::Time)
+            .or_else(|| Currency::from_code(name).map(Target::Currency)),
+        _ => None,
+    })
+    .boxed();
+
+    let percent = just(Token::Simple(Simple::Punc(Punc::Percent)))
+        .to(Target::Percent)
+        .boxed();
+
+    let base = just(Token::Simple(Simple::Identifier("base")))
+        .ignore_then(expression().with_span())
+        .map(Target::Base)
+        .boxed();
 
-        let factor =
-            binary_op!(unary, (Punc::Star => BinaryOp::Mul), (Punc::Slash => BinaryOp::Div))
-                .boxed();
+    let zone = just(Token::Simple(Simple::Identifier("tz")))
+        .ignore_then(zone())
+        .map(Target::TimeZone)
+        .boxed();
+
+    let data = data_unit()
+        .map(|unit| match unit {
+            DataTarget::Data(unit) => Target::Data(unit),
+            DataTarget::Rate(unit) => Target::DataRate(unit),
+        })
+        .boxed();
+
+    choice((radix, base, zone, fraction, data, percent))
+        .labelled("an output format")
+        .boxed()
+}
+
+enum DataTarget {
+    Data(DataUnit),
+    Rate(RateUnit),
+}
+
+/// A unit of information like `GiB`, or a data rate like `Mbit/s` or `Mbps`.
+fn data_unit<'src: 'tok, 'tok>(
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, DataTarget, ParserExtra<'src, 'tok>> {
+    let data = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier(name)) => DataUnit::from_symbol(name),
+        _ => None,
+    });
+
+    let time = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier(name)) => TimeUnit::from_name(name),
+        _ => None,
+    });
+
+    let per = just(Token::Simple(Simple::Punc(Punc::Slash))).ignore_then(time);
+
+    let rate = select_token(|token, _| match token {
+        Token::Simple(Simple::Identifier(name)) => RateUnit::from_symbol(name),
+        _ => None,
+    });
+
+    choice((
+        data.then(per.or_not()).map(|(data, time)| {
+            time.map_or(DataTarget::Data(data), |time| {
+                DataTarget::Rate(RateUnit { data, time })
+            })
+        }),
+        rate.map(DataTarget::Rate),
+    ))
+    .boxed()
+}
 
-        binary_op!(factor, (Punc::Plus => BinaryOp::Add), (Punc::Minus => BinaryOp::Sub)).boxed()
+/// The name of a time zone, in quotes like `"America/New_York"` or bare like `UTC`.
+fn zone<'src: 'tok, 'tok>(
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, Spanned<Identifier>, ParserExtra<'src, 'tok>> {
+    select_token(|token, span| match token {
+        Token::Simple(Simple::String(name) | Simple::Identifier(name)) => Some(Spanned::new(
+            Identifier::new(Spanned::new(RODEO.get_or_intern(name), span)),
+            span,
+        )),
+        _ => None,
     })
+    .labelled("a time zone")
     .boxed()
 }
 
 fn ident<'src: 'tok, 'tok>(
 ) -> impl Parser<'tok, ParserInput<'src, 'tok>, Identifier, ParserExtra<'src, 'tok>> {
-    select! {
-        Token::Simple(Simple::Identifier(ident)) = e => Identifier::new(Spanned::new(RODEO.get_or_intern(ident), e.span())),
-    }
+    select_token(|token, span| match token {
+        Token::Simple(Simple::Identifier(ident)) => Some(Identifier::new(Spanned::new(
+            RODEO.get_or_intern(ident),
+            span,
+        ))),
+        _ => None,
+    })
     .boxed()
 }
 
+// `select!` reports its errors one token past where it failed, so they end up shadowing the
+// errors of the parsers it is tried alongside.
+fn select_token<'src: 'tok, 'tok, O>(
+    f: impl Fn(&'tok Token<'src>, Span) -> Option<O>,
+) -> impl Parser<'tok, ParserInput<'src, 'tok>, O, ParserExtra<'src, 'tok>> {
+    custom(move |inp| {
+        let before = inp.offset();
+        let token = inp.next_ref();
+        let span = inp.span_since(before);
+
+        token.and_then(|token| f(token, span)).ok_or_else(|| {
+            <SyntaxError<_, _> as chumsky::error::Error<ParserInput<'src, 'tok>>>::expected_found(
+                None,
+                token.map(MaybeRef::Ref),
+                span,
+            )
+        })
+    })
+}
+
 trait SpannedExt<'src: 'tok, 'tok, O> {
     fn with_span(
         self,
@@ -170,6 +663,8 @@ trait SpannedExt<'src: 'tok, 'tok, O> {
     fn parenthesized(
         self,
     ) -> impl Parser<'tok, ParserInput<'src, 'tok>, O, ParserExtra<'src, 'tok>>;
+
+    fn bracketed(self) -> impl Parser<'tok, ParserInput<'src, 'tok>, O, ParserExtra<'src, 'tok>>;
 }
 
 impl<'src: 'tok, 'tok, P, O> SpannedExt<'src, 'tok, O> for P
@@ -185,29 +680,52 @@ where
     fn parenthesized(
         self,
     ) -> impl Parser<'tok, ParserInput<'src, 'tok>, O, ParserExtra<'src, 'tok>> {
-        self.nested_in(select_ref! {
-            Token::Parentheses(tokens) = e => tokens.as_slice().spanned(e.span()),
-        })
+        self.nested_in(select_token(|token, span| match token {
+            Token::Parentheses(tokens) => Some(tokens.as_slice().spanned(span.to_end())),
+            _ => None,
+        }))
     }
-}
 
-fn rational_from_str(before: &str, after: Option<&str>, radix: u32) -> Rational {
-    let before = before
-        .chars()
-        .rev()
-        .map(|c| Natural::from(c.to_digit(radix).unwrap()))
-        .collect();
+    fn bracketed(self) -> impl Parser<'tok, ParserInput<'src, 'tok>, O, ParserExtra<'src, 'tok>> {
+        self.nested_in(select_token(|token, span| match token {
+            Token::Brackets(tokens) => Some(tokens.as_slice().spanned(span.to_end())),
+            _ => None,
+        }))
+    }
+}
 
-    let after = after.map_or_else(Vec::new, |after| {
-        after
+fn rational_from_str(
+    before: &str,
+    after: Option<&str>,
+    repeating: Option<&str>,
+    exponent: Option<&str>,
+    radix: Radix,
+) -> Rational {
+    let digits = |digits: &str| {
+        digits
             .chars()
-            .map(|c| Natural::from(c.to_digit(radix).unwrap()))
-            .collect()
-    });
+            .filter(|c| *c != '_')
+            .map(|c| Natural::from(c.to_digit(radix.to_u32()).unwrap()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut before = digits(before);
+    before.reverse();
 
-    Rational::from_digits(
-        &Natural::from(radix),
+    let after = after.map_or_else(Vec::new, digits);
+    let repeating = repeating.map_or_else(Vec::new, digits);
+
+    let mantissa = Rational::from_digits(
+        &Natural::from(radix.to_u32()),
         before,
-        RationalSequence::from_vec(after),
-    )
+        RationalSequence::from_vecs(after, repeating),
+    );
+
+    let scale = exponent.map_or(Rational::ONE, |exponent| {
+        let exponent = exponent.replace('_', "").parse::<i64>().unwrap();
+
+        Rational::from(radix.exponent_base()).pow(exponent)
+    });
+
+    mantissa * scale
 }
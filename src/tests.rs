@@ -0,0 +1,660 @@
+use crate::{
+    currency::Rates,
+    diagnostics::{
+        error::{convert, Error},
+        explain,
+        report::json,
+        Diag,
+    },
+    evaluator::{Evaluator, Output, Value},
+    json::Json,
+    lexer, parser,
+    span::{File, FileId, Span},
+    time::{
+        zone::{Local, Zone},
+        DateTime,
+    },
+};
+use chumsky::{input::Input as _, span::Span as _, Parser as _};
+
+/// Evaluates one line and returns its result, or the first error.
+fn run(evaluator: &mut Evaluator, input: &str) -> Result<Option<Output>, Error> {
+    let file_id = File::Repl(FileId::new(0));
+
+    let tokens = lexer::lexer()
+        .parse(input.with_context(file_id))
+        .into_result()
+        .map_err(|errors| convert(&errors[0]).remove(0))?;
+
+    let eoi = tokens
+        .last()
+        .map_or_else(|| Span::zero(file_id), |(_, span)| span.to_end());
+
+    let statement = parser::repl()
+        .parse(tokens.spanned(eoi))
+        .into_result()
+        .map_err(|errors| convert(&errors[0]).remove(0))?;
+
+    match evaluator.evaluate_statement(statement)? {
+        core::ops::ControlFlow::Continue(output) => Ok(output),
+        core::ops::ControlFlow::Break(()) => Ok(None),
+    }
+}
+
+/// Evaluates one line and returns the displayed result, or the message of the first error.
+pub fn eval(evaluator: &mut Evaluator, input: &str) -> String {
+    match run(evaluator, input) {
+        Ok(output) => output.map_or_else(String::new, |output| output.display(evaluator.options())),
+        Err(err) => err.message().into_owned(),
+    }
+}
+
+/// Evaluates one line and returns its value as JSON, like `--format json` writes it.
+pub fn value_json(evaluator: &mut Evaluator, input: &str) -> String {
+    match run(evaluator, input) {
+        Ok(Some(output)) => output.value.to_json(evaluator.options()).to_string(),
+        Ok(None) => panic!("`{input}` has no value"),
+        Err(err) => panic!("`{input}` failed with `{}`", err.message()),
+    }
+}
+
+/// Evaluates one line that fails and returns the code of its first error.
+pub fn code(evaluator: &mut Evaluator, input: &str) -> &'static str {
+    match run(evaluator, input) {
+        Ok(_) => panic!("`{input}` gave a value instead of an error"),
+        Err(err) => err.code(),
+    }
+}
+
+/// Evaluates one line that fails and returns its first error as JSON, with its code, labels and
+/// notes.
+pub fn diagnose(evaluator: &mut Evaluator, input: &str) -> String {
+    match run(evaluator, input) {
+        Ok(_) => panic!("`{input}` gave a value instead of an error"),
+        Err(err) => json(&err).to_string(),
+    }
+}
+
+#[test]
+fn plus_minus_needs_spaces() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "5+-3"), "2");
+    assert_eq!(eval(&mut evaluator, "5 +-3"), "2");
+    assert_eq!(eval(&mut evaluator, "x = 4"), "");
+    assert_eq!(eval(&mut evaluator, "x+-1"), "3");
+    assert_eq!(eval(&mut evaluator, "5 +- 0.1"), "5.00 ± 0.10");
+}
+
+#[test]
+fn reused_measurements_are_correlated() {
+    let mut evaluator = Evaluator::default();
+
+    eval(&mut evaluator, "x = 5 +- 0.1");
+    eval(&mut evaluator, "y = 3 +- 0.2");
+
+    assert_eq!(eval(&mut evaluator, "x - x"), "0");
+    assert_eq!(eval(&mut evaluator, "x / x"), "1");
+    assert_eq!(eval(&mut evaluator, "x + x"), "10.00 ± 0.20");
+    assert_eq!(eval(&mut evaluator, "x - y"), "2.00 ± 0.22");
+    assert_eq!(
+        eval(&mut evaluator, "(5 +- 0.1) - (5 +- 0.1)"),
+        "0.00 ± 0.14"
+    );
+}
+
+#[test]
+fn percent_or_remainder() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "10 % 3"), "1");
+    assert_eq!(eval(&mut evaluator, "10 % ~3"), "-2");
+    assert_eq!(eval(&mut evaluator, "200 + 15%"), "230");
+    assert_eq!(eval(&mut evaluator, "200 - 15%"), "170");
+    assert_eq!(eval(&mut evaluator, "(15%) - 3"), "-2.85");
+}
+
+#[test]
+fn percentages_multiply_in_either_order() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "200 * 15%"), "30");
+    assert_eq!(eval(&mut evaluator, "15% * 200"), "30");
+    assert_eq!(eval(&mut evaluator, "50% * 15%"), "7.5%");
+    assert_eq!(eval(&mut evaluator, "15% * 50%"), "7.5%");
+    assert_eq!(eval(&mut evaluator, "15% / 3"), "5%");
+}
+
+#[test]
+fn statistics_keep_units() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(
+        eval(&mut evaluator, "stdev([1 min, 3 min]) to s"),
+        "84.8528137423857 s"
+    );
+    assert_eq!(eval(&mut evaluator, "median([1 EUR, 2 EUR])"), "1.5 EUR");
+    assert_eq!(eval(&mut evaluator, "max([1 GB, 2 GB])"), "2 GB");
+    assert_eq!(eval(&mut evaluator, "min([1 GiB, 2 GB])"), "1 GiB");
+    assert_eq!(
+        eval(&mut evaluator, "percentile([1 s, 2 s, 3 s], 50)"),
+        "2 s"
+    );
+    assert_eq!(eval(&mut evaluator, "median([10%, 20%])"), "15%");
+    assert_eq!(
+        eval(&mut evaluator, "variance([1 min, 3 min])"),
+        "`variance` cannot be applied to this list"
+    );
+}
+
+#[test]
+fn unit_typos_are_suggested() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(
+        eval(&mut evaluator, "1 Gib"),
+        "Unknown unit `Gib`, did you mean `GiB`?"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "90 mni"),
+        "Unknown unit `mni`, did you mean `min`?"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "5 eur"),
+        "Unknown unit `eur`, did you mean `EUR`?"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "1 KB"),
+        "Unknown unit `KB`, write `kB` for 1000 bytes or `KiB` for 1024 bytes"
+    );
+}
+
+#[test]
+fn zones_keep_their_history() {
+    let mut evaluator = Evaluator::default();
+
+    // The United States started daylight saving time in April until 2007.
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2005-04-01T12:00Z to tz \"America/New_York\""
+        ),
+        "2005-04-01 07:00:00 EST (UTC-05:00)"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "2010-07-01T00:00Z to tz \"Europe/Moscow\""),
+        "2010-07-01 04:00:00 MSD (UTC+04:00)"
+    );
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2018-01-15T12:00Z to tz \"America/Sao_Paulo\""
+        ),
+        "2018-01-15 10:00:00 UTC-02:00"
+    );
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2026-07-15T12:00Z to tz \"Australia/Lord_Howe\""
+        ),
+        "2026-07-15 22:30:00 UTC+10:30"
+    );
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2026-01-15T12:00Z to tz \"Australia/Lord_Howe\""
+        ),
+        "2026-01-15 23:00:00 UTC+11:00"
+    );
+
+    for name in ["Europe/Kiev", "US/Eastern", "Africa/Casablanca", "new york"] {
+        assert!(Zone::find(name).is_some(), "`{name}` is not found");
+    }
+
+    assert_eq!(
+        eval(&mut evaluator, "2026-01-15T12:00Z to tz \"US/Eastern\""),
+        "2026-01-15 07:00:00 EST (UTC-05:00)"
+    );
+}
+
+#[test]
+fn daylight_saving_transitions() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2026-03-08T06:59:59Z to tz \"America/New_York\""
+        ),
+        "2026-03-08 01:59:59 EST (UTC-05:00)"
+    );
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2026-03-08T07:00Z to tz \"America/New_York\""
+        ),
+        "2026-03-08 03:00:00 EDT (UTC-04:00)"
+    );
+    assert_eq!(
+        eval(
+            &mut evaluator,
+            "2026-10-25T00:59:59Z to tz \"Europe/Berlin\""
+        ),
+        "2026-10-25 02:59:59 CEST (UTC+02:00)"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "2026-10-25T01:00Z to tz \"Europe/Berlin\""),
+        "2026-10-25 02:00:00 CET (UTC+01:00)"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "2026-07-01T12:00[Europe/Berlin]"),
+        "2026-07-01 10:00:00 UTC"
+    );
+}
+
+#[test]
+fn local_times_around_transitions() {
+    let new_york = Zone::find("America/New_York").unwrap();
+    let local = |text: &str| DateTime::parse(text).unwrap();
+
+    assert!(matches!(
+        new_york.resolve(&local("2026-03-08T02:30")),
+        Local::Nonexistent
+    ));
+    assert!(matches!(
+        new_york.resolve(&local("2026-11-01T01:30")),
+        Local::Ambiguous(-14_400, -18_000)
+    ));
+    assert!(matches!(
+        new_york.resolve(&local("2026-11-01T02:30")),
+        Local::Unique(date_time) if date_time == local("2026-11-01T07:30Z")
+    ));
+
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(
+        eval(&mut evaluator, "2026-03-08T02:30[America/New_York]"),
+        "This time does not exist in `America/New_York`"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "2026-11-01T01:30[America/New_York]"),
+        "This time happens twice in `America/New_York`"
+    );
+}
+
+#[test]
+fn currencies_convert_through_the_base() {
+    let mut evaluator = Evaluator::default();
+
+    evaluator.set_rates(Rates::parse("date,2026-10-01\nUSD,1.0712\nGBP,0.85\n").unwrap());
+
+    assert_eq!(eval(&mut evaluator, "120 EUR to USD"), "128.544 USD");
+    assert_eq!(eval(&mut evaluator, "107.12 USD to EUR"), "100 EUR");
+    assert_eq!(eval(&mut evaluator, "85 GBP to USD"), "107.12 USD");
+    assert_eq!(
+        eval(&mut evaluator, "1 JPY to USD"),
+        "No exchange rate for JPY"
+    );
+
+    evaluator.set_rates(Rates::parse("base,USD\nEUR,0.5\n").unwrap());
+
+    assert_eq!(eval(&mut evaluator, "3 USD to EUR"), "1.5 EUR");
+    assert_eq!(eval(&mut evaluator, "1 EUR to USD"), "2 USD");
+    assert!(Rates::parse("base,USD\nUSD,2\n").is_err());
+}
+
+#[test]
+fn undefined_names_suggest_functions_and_units() {
+    let mut evaluator = Evaluator::default();
+
+    evaluator.insert("radius", Value::Number(2.into()));
+
+    for (input, suggestion) in [
+        ("radus", "radius"),
+        ("sqr", "sqrt"),
+        ("mni", "min"),
+        ("tody", "today"),
+        ("GiBB", "GiB"),
+    ] {
+        assert!(
+            diagnose(&mut evaluator, input).contains(&format!("Did you mean `{suggestion}`?")),
+            "`{input}` does not suggest `{suggestion}`"
+        );
+    }
+}
+
+#[test]
+fn assigning_to_a_unit_warns() {
+    let mut evaluator = Evaluator::default();
+    let codes = |evaluator: &mut Evaluator, input: &str| {
+        eval(evaluator, input);
+
+        evaluator
+            .take_warnings()
+            .iter()
+            .map(Diag::code)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(codes(&mut evaluator, "min = 3"), ["W0004"]);
+    assert!(codes(&mut evaluator, "min = min + 1").is_empty());
+    assert_eq!(codes(&mut evaluator, "EUR = 2"), ["W0004"]);
+    assert!(codes(&mut evaluator, "distance = 3").is_empty());
+    assert_eq!(eval(&mut evaluator, "2 min"), "2 min");
+    assert_eq!(eval(&mut evaluator, "min * 2"), "8");
+}
+
+#[test]
+fn angles_are_precise() {
+    let mut evaluator = Evaluator::default();
+
+    evaluator.insert("i", Value::I);
+    eval(&mut evaluator, "precision 40");
+
+    assert_eq!(
+        eval(&mut evaluator, "arg(-1)"),
+        "3.141592653589793238462643383279502884197"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "arg(-i)"),
+        "-1.570796326794896619231321691639751442099"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "arg(-1 - i)"),
+        "-2.356194490192344928846982537459627163148"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "arg(3 + 4*i)"),
+        "0.9272952180016122324285124629224288040571"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "arg(1e-30 + i)"),
+        "1.570796326794896619231321691638751442099"
+    );
+    assert_eq!(eval(&mut evaluator, "arg(2)"), "0");
+}
+
+#[test]
+fn syntax_errors_have_their_own_codes() {
+    let mut evaluator = Evaluator::default();
+
+    for (input, expected) in [
+        ("1 $ 2", "P0003"),
+        ("(1 + 2", "P0029"),
+        ("1 + 2)", "P0030"),
+        ("now in \"Europe/Berlin", "P0031"),
+        ("2026-02-30", "P0032"),
+        ("2026-10-17T09:30+02:00[Europe/Berlin]", "P0033"),
+        ("1e1000000", "P0034"),
+        ("10 % -3", "P0035"),
+        ("5 mni", "P0036"),
+        ("1__000", "P0037"),
+    ] {
+        assert_eq!(code(&mut evaluator, input), expected, "for `{input}`");
+        assert!(explain(expected).is_some(), "`{expected}` is not explained");
+    }
+}
+
+#[test]
+fn matrices_are_lists_of_rows() {
+    let mut evaluator = Evaluator::default();
+
+    eval(&mut evaluator, "m = [[1, 2], [3, 4]]");
+
+    assert_eq!(eval(&mut evaluator, "len(m)"), "2");
+    assert_eq!(eval(&mut evaluator, "sum(m)"), "[4, 6]");
+    assert_eq!(eval(&mut evaluator, "mean(m)"), "[2, 3]");
+    assert_eq!(eval(&mut evaluator, "m[-1]"), "[3, 4]");
+    assert_eq!(eval(&mut evaluator, "len([[1, 2], [3]])"), "2");
+    assert_eq!(eval(&mut evaluator, "det(m)"), "-2");
+}
+
+#[test]
+fn separators_sit_between_digits() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "1_000_000"), "1000000");
+    assert_eq!(eval(&mut evaluator, "0xff_ff"), "65535");
+    assert_eq!(eval(&mut evaluator, "1.000_5"), "1.0005");
+    assert_eq!(eval(&mut evaluator, "1e1_0"), "10000000000");
+
+    for input in ["1_", "1__0", "0b1_", "1.5_", "1e1_"] {
+        assert_eq!(code(&mut evaluator, input), "P0037", "for `{input}`");
+    }
+}
+
+#[test]
+fn values_as_json() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(
+        value_json(&mut evaluator, "1/3"),
+        r#"{"type":"number","numerator":"1","denominator":"3","decimal":"0.3333333333333333"}"#
+    );
+    assert_eq!(
+        value_json(&mut evaluator, "15%"),
+        r#"{"type":"percent","numerator":"3","denominator":"20","decimal":"15%"}"#
+    );
+    assert_eq!(
+        value_json(&mut evaluator, "90 min"),
+        r#"{"type":"duration","seconds":{"numerator":"5400","denominator":"1"},"decimal":"1 h 30 min"}"#
+    );
+    assert_eq!(
+        value_json(&mut evaluator, "[true, 2 EUR]"),
+        r#"{"type":"list","elements":[{"type":"bool","value":true,"decimal":"true"},{"type":"money","currency":"EUR","amount":{"numerator":"2","denominator":"1"},"decimal":"2 EUR"}],"decimal":"[true, 2 EUR]"}"#
+    );
+    assert_eq!(
+        value_json(&mut evaluator, "factor(12)"),
+        r#"{"type":"factors","negative":false,"factors":[{"prime":"2","exponent":"2"},{"prime":"3","exponent":"1"}],"decimal":"2^2 · 3"}"#
+    );
+    assert_eq!(
+        Json::string("say \"hi\"\n\ttab\u{1}").to_string(),
+        r#""say \"hi\"\n\ttab\u0001""#
+    );
+}
+
+#[test]
+fn errors_have_codes_and_explanations() {
+    let mut evaluator = Evaluator::default();
+
+    for code in (1..=37)
+        .map(|code| format!("P{code:04}"))
+        .chain((1..=4).map(|code| format!("W{code:04}")))
+    {
+        assert!(explain(&code).is_some(), "`{code}` is not explained");
+    }
+
+    assert_eq!(explain("p0009"), explain("P0009"));
+    assert_eq!(explain("P9999"), None);
+
+    assert_eq!(code(&mut evaluator, "1 +"), "P0002");
+    assert_eq!(
+        diagnose(&mut evaluator, "1 / 0"),
+        r#"{"code":"P0009","message":"Division by zero","severity":"error","spans":[{"start":4,"end":5,"style":"primary","message":"This value is zero"}],"notes":[]}"#
+    );
+    assert_eq!(
+        diagnose(&mut evaluator, "x"),
+        r#"{"code":"P0001","message":"Undefined variable `x`","severity":"error","spans":[{"start":0,"end":1,"style":"primary","message":"This variable is undefined"}],"notes":["Consider assigning a value to `x`:","x = <value>"]}"#
+    );
+
+    assert_eq!(eval(&mut evaluator, "explain P0009"), "");
+    assert_eq!(
+        evaluator.take_output(),
+        format!("{}\n", explain("P0009").unwrap())
+    );
+    assert_eq!(code(&mut evaluator, "explain P9999"), "P0006");
+}
+
+#[test]
+fn results_in_other_bases() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "255 to hex"), "0xff");
+    assert_eq!(eval(&mut evaluator, "255 to bin"), "0b11111111");
+    assert_eq!(eval(&mut evaluator, "255 to oct"), "0o377");
+    assert_eq!(eval(&mut evaluator, "255 to base 36"), "73 (base 36)");
+    assert_eq!(eval(&mut evaluator, "-10 to hex"), "-0xa");
+    assert_eq!(eval(&mut evaluator, "1.5 to hex"), "0x1.8");
+    assert_eq!(eval(&mut evaluator, "0.1 to bin"), "0b0.0(0011)");
+    assert_eq!(eval(&mut evaluator, "1/3 to base 3"), "0.1 (base 3)");
+
+    for input in ["10 to base 1", "10 to base 37"] {
+        assert_eq!(eval(&mut evaluator, input), "Invalid base", "for `{input}`");
+    }
+}
+
+#[test]
+fn integer_operators_and_wrapping() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "5 & 3"), "1");
+    assert_eq!(eval(&mut evaluator, "5 | 3"), "7");
+    assert_eq!(eval(&mut evaluator, "5 xor 3"), "6");
+    assert_eq!(eval(&mut evaluator, "~5"), "-6");
+    assert_eq!(eval(&mut evaluator, "1 << 10"), "1024");
+    assert_eq!(eval(&mut evaluator, "1024 >> 3"), "128");
+    assert_eq!(eval(&mut evaluator, "-7 div 2"), "-4");
+    assert_eq!(eval(&mut evaluator, "-7 mod 2"), "1");
+    assert_eq!(
+        eval(&mut evaluator, "5.5 & 1"),
+        "`&` can only be applied to integers"
+    );
+
+    eval(&mut evaluator, "width u8");
+
+    assert_eq!(eval(&mut evaluator, "200 + 100"), "44");
+    assert_eq!(eval(&mut evaluator, "1 << 8"), "0");
+    assert_eq!(eval(&mut evaluator, "~0"), "255");
+    assert_eq!(eval(&mut evaluator, "0 - 1 to hex"), "0xff");
+
+    eval(&mut evaluator, "width i8");
+
+    assert_eq!(eval(&mut evaluator, "127 + 1"), "-128");
+    assert_eq!(eval(&mut evaluator, "-128 - 1"), "127");
+    assert_eq!(eval(&mut evaluator, "~0"), "-1");
+
+    eval(&mut evaluator, "width i32");
+
+    assert_eq!(eval(&mut evaluator, "2147483647 + 1"), "-2147483648");
+
+    eval(&mut evaluator, "width off");
+
+    assert_eq!(eval(&mut evaluator, "127 + 1"), "128");
+    assert_eq!(
+        eval(&mut evaluator, "width 8"),
+        "Expected an integer type or `off`, but found `8`"
+    );
+}
+
+#[test]
+fn scientific_and_prefixed_literals() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "1e3"), "1000");
+    assert_eq!(eval(&mut evaluator, "2.5E2"), "250");
+    assert_eq!(eval(&mut evaluator, "1.5e-3"), "0.0015");
+    assert_eq!(eval(&mut evaluator, "0xff"), "255");
+    assert_eq!(eval(&mut evaluator, "0b1010"), "10");
+    assert_eq!(eval(&mut evaluator, "0o17"), "15");
+    // Binary exponents count powers of two.
+    assert_eq!(eval(&mut evaluator, "0x1.8p1"), "3");
+    assert_eq!(eval(&mut evaluator, "1e100000 / 1e99999"), "10");
+}
+
+#[test]
+fn repeating_decimal_literals() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "0.(3) to fraction"), "1/3");
+    assert_eq!(eval(&mut evaluator, "0.1(6) to fraction"), "1/6");
+    assert_eq!(eval(&mut evaluator, "1.(142857) to fraction"), "8/7");
+    assert_eq!(eval(&mut evaluator, "0.12_3... to fraction"), "37/300");
+    assert_eq!(eval(&mut evaluator, "0.(9)"), "1");
+    assert_eq!(eval(&mut evaluator, "0.(3)"), "0.3333333333333333 ≈ 1/3");
+    assert_eq!(eval(&mut evaluator, "7/3 to mixed"), "2 1/3");
+}
+
+#[test]
+fn repeating_decimal_output() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "1/3 to repeating"), "0.(3)");
+    assert_eq!(eval(&mut evaluator, "22/7 to repeating"), "3.(142857)");
+    assert_eq!(eval(&mut evaluator, "-1/6 to repeating"), "-0.1(6)");
+    assert_eq!(eval(&mut evaluator, "2.5 to repeating"), "2.5");
+    assert_eq!(eval(&mut evaluator, "1/3 to bin"), "0b0.(01)");
+    assert_eq!(eval(&mut evaluator, "1/7 to hex"), "0x0.(249)");
+    assert_eq!(
+        eval(&mut evaluator, "1/97 to repeating"),
+        "0.(010309278350515463917525773195876288659793814432989690721649484536082474226804123711340206185567)"
+    );
+    // Periods too long to write out are cut off.
+    assert!(eval(&mut evaluator, "1/65537 to repeating").ends_with('…'));
+}
+
+#[test]
+fn exact_linear_algebra() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(
+        eval(&mut evaluator, "[[1, 2], [3, 4]] * [[5, 6], [7, 8]]"),
+        "[[19, 22], [43, 50]]"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "[[1, 2], [3, 4]] * 2"),
+        "[[2, 4], [6, 8]]"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "inv([[1, 2], [3, 4]])"),
+        "[[-2, 1], [1.5, -0.5]]"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "transpose([[1, 2, 3], [4, 5, 6]])"),
+        "[[1, 4], [2, 5], [3, 6]]"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "solve([[2, 1], [1, 3]], [3, 5])"),
+        "[0.8, 1.4]"
+    );
+    assert_eq!(eval(&mut evaluator, "det([[1, 2], [2, 4]])"), "0");
+    assert_eq!(eval(&mut evaluator, "rank([[1, 2], [2, 4]])"), "1");
+    assert_eq!(
+        eval(&mut evaluator, "inv([[1, 2], [2, 4]])"),
+        "`inv` cannot be applied to this matrix"
+    );
+    assert_eq!(
+        eval(&mut evaluator, "[[1, 2], [3, 4]] + [[1, 2, 3]]"),
+        "`+` cannot be applied to values of these shapes"
+    );
+}
+
+#[test]
+fn primes_and_factors() {
+    let mut evaluator = Evaluator::default();
+
+    assert_eq!(eval(&mut evaluator, "isprime(97)"), "true");
+    assert_eq!(eval(&mut evaluator, "isprime(1)"), "false");
+    // The Mersenne prime 2^61 - 1, and the Fermat number 2^64 + 1.
+    assert_eq!(eval(&mut evaluator, "isprime(2305843009213693951)"), "true");
+    assert_eq!(
+        eval(&mut evaluator, "factor(18446744073709551617)"),
+        "274177 · 67280421310721"
+    );
+    assert_eq!(eval(&mut evaluator, "factor(360)"), "2^3 · 3^2 · 5");
+    assert_eq!(eval(&mut evaluator, "factor(-12)"), "-2^2 · 3");
+    assert_eq!(eval(&mut evaluator, "factor(360) + 1"), "361");
+    assert_eq!(
+        eval(&mut evaluator, "factor(0)"),
+        "`factor` expects a non-zero integer"
+    );
+    assert_eq!(eval(&mut evaluator, "nextprime(100)"), "101");
+    assert_eq!(eval(&mut evaluator, "gcd(12, 18)"), "6");
+    assert_eq!(eval(&mut evaluator, "lcm(4, 6)"), "12");
+    assert_eq!(eval(&mut evaluator, "modpow(2, 100, 7)"), "2");
+    assert_eq!(eval(&mut evaluator, "modinv(3, 11)"), "4");
+    assert_eq!(eval(&mut evaluator, "totient(36)"), "12");
+    assert_eq!(
+        eval(&mut evaluator, "modinv(2, 4)"),
+        "`modinv` expects a number coprime to the modulus"
+    );
+}
@@ -0,0 +1,415 @@
+use malachite::{
+    num::{
+        arithmetic::traits::{Abs, DivMod, Floor, Pow},
+        basic::traits::Zero,
+        conversion::traits::{IsInteger, RoundingFrom},
+    },
+    rounding_modes::RoundingMode,
+    Integer, Natural, Rational,
+};
+
+pub mod zone;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// The earliest and latest years a date can have, so every date has four digits.
+pub const YEARS: core::ops::RangeInclusive<i64> = 1..=9999;
+
+/// A moment in time, counted in UTC on the proleptic Gregorian calendar without leap seconds.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DateTime {
+    /// The seconds since 1970-01-01T00:00:00Z.
+    pub seconds: Rational,
+    /// Whether this is a whole day written without a time, which is displayed the same way.
+    pub date: bool,
+}
+
+impl DateTime {
+    /// Parses an ISO 8601 date like `2026-10-17`, or a date and time like `2026-10-17T09:30Z`,
+    /// `2026-10-17T09:30:15.25` or `2026-10-17T09:30+02:00`, where the time is in UTC unless it
+    /// has an offset.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (date, time) = match text.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (text, None),
+        };
+
+        let mut fields = date.splitn(3, '-');
+        let year = fields.next()?.parse::<i64>().ok()?;
+        let month = fields.next()?.parse::<u32>().ok()?;
+        let day = fields.next()?.parse::<u32>().ok()?;
+
+        if !YEARS.contains(&year) || !(1..=12).contains(&month) {
+            return None;
+        }
+
+        if !(1..=days_in_month(year, month)).contains(&day) {
+            return None;
+        }
+
+        let midnight = Rational::from(days_from_civil(year, month, day) * SECONDS_PER_DAY);
+
+        let Some(time) = time else {
+            return Some(Self {
+                seconds: midnight,
+                date: true,
+            });
+        };
+
+        let (time, offset) = split_offset(time)?;
+        let mut fields = time.splitn(3, ':');
+
+        let hours = fields
+            .next()?
+            .parse::<u32>()
+            .ok()
+            .filter(|hours| *hours < 24)?;
+        let minutes = fields
+            .next()?
+            .parse::<u32>()
+            .ok()
+            .filter(|minutes| *minutes < 60)?;
+        let seconds = fields.next().map_or(Some(Rational::ZERO), parse_seconds)?;
+
+        Some(Self {
+            seconds: midnight + Rational::from(hours * 3600 + minutes * 60) + seconds
+                - Rational::from(offset),
+            date: false,
+        })
+    }
+
+    /// The moment `seconds` later, which stays a date when moved by whole days, unless it is
+    /// outside of [`YEARS`].
+    pub fn shifted(&self, seconds: &Rational) -> Option<Self> {
+        let shifted = Self {
+            seconds: &self.seconds + seconds,
+            date: self.date && (seconds / Rational::from(SECONDS_PER_DAY)).is_integer(),
+        };
+
+        YEARS.contains(&shifted.civil().0).then_some(shifted)
+    }
+
+    /// The start of the day this moment falls on.
+    pub fn day(&self) -> Self {
+        let (days, _) = self.days_and_seconds();
+
+        Self {
+            seconds: Rational::from(days * SECONDS_PER_DAY),
+            date: true,
+        }
+    }
+
+    /// The year, month and day.
+    pub fn civil(&self) -> (i64, u32, u32) {
+        civil_from_days(self.days_and_seconds().0)
+    }
+
+    /// Writes the moment like `2026-10-17 09:30:00 UTC`, or just `2026-10-17` for a date.
+    pub fn display(&self) -> String {
+        if self.date {
+            return self.display_date();
+        }
+
+        format!("{} {} UTC", self.display_date(), self.display_time())
+    }
+
+    /// Writes the moment in ISO 8601, like `2026-10-17T09:30:00Z` or `2026-10-17`.
+    pub fn display_iso(&self) -> String {
+        if self.date {
+            return self.display_date();
+        }
+
+        format!("{}T{}Z", self.display_date(), self.display_time())
+    }
+
+    /// Writes the moment in ISO 8601 with an offset from UTC in seconds, like
+    /// `2026-11-01T01:30:00-04:00`.
+    pub fn display_iso_with_offset(&self, offset: i64) -> String {
+        let local = Self {
+            seconds: &self.seconds + Rational::from(offset),
+            date: false,
+        };
+
+        format!(
+            "{}T{}{}",
+            local.display_date(),
+            local.display_time(),
+            zone::display_offset(offset)
+        )
+    }
+
+    fn display_date(&self) -> String {
+        let (year, month, day) = self.civil();
+
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+
+    fn display_time(&self) -> String {
+        let (_, seconds) = self.days_and_seconds();
+        let whole = i64::rounding_from(&seconds, RoundingMode::Floor).0;
+
+        format!(
+            "{:02}:{:02}:{:02}{}",
+            whole / 3600,
+            whole / 60 % 60,
+            whole % 60,
+            display_fraction(&(seconds - Rational::from(whole)))
+        )
+    }
+
+    /// The days since the epoch, and the seconds since the start of that day.
+    fn days_and_seconds(&self) -> (i64, Rational) {
+        let day = Rational::from(SECONDS_PER_DAY);
+        let days = (&self.seconds / &day).floor();
+        let seconds = &self.seconds - Rational::from(days.clone()) * day;
+
+        (i64::try_from(&days).unwrap_or(i64::MAX), seconds)
+    }
+}
+
+/// A unit that durations can be written in, like the `min` in `90 min`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimeUnit {
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+/// Every name a unit of time can be written with.
+const NAMES: &[(&str, TimeUnit)] = &[
+    ("ms", TimeUnit::Millisecond),
+    ("millisecond", TimeUnit::Millisecond),
+    ("milliseconds", TimeUnit::Millisecond),
+    ("s", TimeUnit::Second),
+    ("sec", TimeUnit::Second),
+    ("second", TimeUnit::Second),
+    ("seconds", TimeUnit::Second),
+    ("min", TimeUnit::Minute),
+    ("minute", TimeUnit::Minute),
+    ("minutes", TimeUnit::Minute),
+    ("h", TimeUnit::Hour),
+    ("hr", TimeUnit::Hour),
+    ("hour", TimeUnit::Hour),
+    ("hours", TimeUnit::Hour),
+    ("day", TimeUnit::Day),
+    ("days", TimeUnit::Day),
+    ("week", TimeUnit::Week),
+    ("weeks", TimeUnit::Week),
+];
+
+impl TimeUnit {
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMES
+            .iter()
+            .find(|(unit_name, _)| *unit_name == name)
+            .map(|(_, unit)| *unit)
+    }
+
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        NAMES.iter().map(|(name, _)| *name)
+    }
+
+    pub fn seconds(self) -> Rational {
+        match self {
+            Self::Millisecond => Rational::from_unsigneds(1u32, 1000),
+            Self::Second => Rational::from(1u32),
+            Self::Minute => Rational::from(60u32),
+            Self::Hour => Rational::from(3600u32),
+            Self::Day => Rational::from(SECONDS_PER_DAY),
+            Self::Week => Rational::from(7 * SECONDS_PER_DAY),
+        }
+    }
+
+    /// The name used after a number of this unit, where days and weeks are spelled out.
+    pub const fn name(self, singular: bool) -> &'static str {
+        match (self, singular) {
+            (Self::Millisecond, _) => "ms",
+            (Self::Second, _) => "s",
+            (Self::Minute, _) => "min",
+            (Self::Hour, _) => "h",
+            (Self::Day, true) => "day",
+            (Self::Day, false) => "days",
+            (Self::Week, true) => "week",
+            (Self::Week, false) => "weeks",
+        }
+    }
+}
+
+impl core::fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.name(true))
+    }
+}
+
+/// Writes a duration in days, hours, minutes and seconds, like `1 day 2 h 30 min`, formatting
+/// the seconds with `f`.
+pub fn display_duration(seconds: &Rational, f: impl Fn(&Rational) -> String) -> String {
+    let sign = if *seconds < 0u32 { "-" } else { "" };
+    let (days, hours, minutes, rest) = split_duration(&seconds.abs());
+
+    let mut parts = vec![];
+
+    if days != 0u32 {
+        parts.push(format!("{days} {}", TimeUnit::Day.name(days == 1u32)));
+    }
+
+    for (amount, unit) in [(hours, TimeUnit::Hour), (minutes, TimeUnit::Minute)] {
+        if amount != 0 {
+            parts.push(format!("{amount} {unit}"));
+        }
+    }
+
+    if rest != 0u32 || parts.is_empty() {
+        parts.push(format!("{} {}", f(&rest), TimeUnit::Second));
+    }
+
+    format!("{sign}{}", parts.join(" "))
+}
+
+/// Writes a duration in ISO 8601, like `P1DT2H30M` or `-PT0.5S`, formatting the seconds with `f`.
+pub fn display_iso_duration(seconds: &Rational, f: impl Fn(&Rational) -> String) -> String {
+    let sign = if *seconds < 0u32 { "-" } else { "" };
+    let (days, hours, minutes, rest) = split_duration(&seconds.abs());
+
+    let days = if days == 0u32 {
+        String::new()
+    } else {
+        format!("{days}D")
+    };
+
+    let mut time = vec![];
+
+    if hours != 0 {
+        time.push(format!("{hours}H"));
+    }
+
+    if minutes != 0 {
+        time.push(format!("{minutes}M"));
+    }
+
+    if rest != 0u32 || (time.is_empty() && days.is_empty()) {
+        time.push(format!("{}S", f(&rest)));
+    }
+
+    if time.is_empty() {
+        format!("{sign}P{days}")
+    } else {
+        format!("{sign}P{days}T{}", time.concat())
+    }
+}
+
+/// Splits a non-negative duration into whole days, hours and minutes, and the remaining seconds.
+fn split_duration(seconds: &Rational) -> (Integer, i64, i64, Rational) {
+    let whole = seconds.clone().floor();
+    let (days, rest) = (&whole).div_mod(Integer::from(SECONDS_PER_DAY));
+    let rest = i64::try_from(&rest).unwrap();
+
+    (
+        days,
+        rest / 3600,
+        rest / 60 % 60,
+        Rational::from(rest % 60) + seconds - Rational::from(whole),
+    )
+}
+
+/// The days since 1970-01-01 of a date, from Howard Hinnant's `days_from_civil`.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * i64::from((month + 9) % 12) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date of a number of days since 1970-01-01, from Howard Hinnant's `civil_from_days`.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = u32::try_from(day_of_year - (153 * shifted_month + 2) / 5 + 1).unwrap();
+    let month = u32::try_from((shifted_month + 2) % 12 + 1).unwrap();
+
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+pub const fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub const fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Splits the offset from UTC off the end of a time, in seconds.
+fn split_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(time) = time.strip_suffix('Z') {
+        return Some((time, 0));
+    }
+
+    let Some(index) = time.rfind(['+', '-']) else {
+        return Some((time, 0));
+    };
+
+    let (time, offset) = time.split_at(index);
+    let (hours, minutes) = offset[1..].split_once(':')?;
+    let (hours, minutes) = (hours.parse::<i64>().ok()?, minutes.parse::<i64>().ok()?);
+    let negative = offset.starts_with('-');
+
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    let offset = hours * 3600 + minutes * 60;
+
+    Some((time, if negative { -offset } else { offset }))
+}
+
+/// Parses seconds like `15` or `15.25`, which must be less than 60.
+fn parse_seconds(text: &str) -> Option<Rational> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let whole = whole.parse::<u32>().ok().filter(|whole| *whole < 60)?;
+
+    if !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let fraction = if fraction.is_empty() {
+        Rational::ZERO
+    } else {
+        Rational::from_naturals(
+            fraction.parse().ok()?,
+            Natural::from(10u32).pow(u64::try_from(fraction.len()).ok()?),
+        )
+    };
+
+    Some(Rational::from(whole) + fraction)
+}
+
+/// Writes the part of a second after the point, like `.25`, to at most nine digits.
+fn display_fraction(fraction: &Rational) -> String {
+    let nanos = i64::rounding_from(
+        &(fraction * Rational::from(1_000_000_000u32)),
+        RoundingMode::Floor,
+    )
+    .0;
+
+    if nanos == 0 {
+        return String::new();
+    }
+
+    format!(".{nanos:09}").trim_end_matches('0').to_string()
+}
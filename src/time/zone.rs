@@ -0,0 +1,131 @@
+//! Time zones from the IANA time zone database, with every change to their offsets and the older
+//! names they still go by, like `Europe/Kiev` and `US/Eastern`.
+
+use super::DateTime;
+use jiff::{
+    tz::{AmbiguousOffset, Offset, TimeZone},
+    Timestamp,
+};
+use malachite::{num::arithmetic::traits::Floor, Rational};
+
+/// A time zone, named like `America/New_York`.
+///
+/// The rules are shared between clones, so a zone is cheap to copy around.
+#[derive(Clone, Debug)]
+pub struct Zone {
+    pub name: &'static str,
+    rules: TimeZone,
+}
+
+impl PartialEq for Zone {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Zone {}
+
+/// How a local time maps to a moment in a zone.
+pub enum Local {
+    Unique(DateTime),
+    /// The clocks pass this time twice, once at each of these offsets.
+    Ambiguous(i64, i64),
+    /// The clocks skip this time.
+    Nonexistent,
+}
+
+impl Zone {
+    /// Finds a zone by its full name or just its city, like `America/New_York` or `new york`.
+    pub fn find(name: &str) -> Option<Self> {
+        let name = name.replace(' ', "_");
+
+        let name = Self::names()
+            .find(|zone| zone.eq_ignore_ascii_case(&name))
+            .or_else(|| {
+                Self::names().find(|zone| {
+                    zone.rsplit_once('/')
+                        .is_some_and(|(_, city)| city.eq_ignore_ascii_case(&name))
+                })
+            })?;
+
+        let (_, data) = jiff_tzdb::get(name)?;
+
+        Some(Self {
+            name,
+            rules: TimeZone::tzif(name, data).ok()?,
+        })
+    }
+
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        jiff_tzdb::available()
+    }
+
+    /// The offset from UTC in seconds at a moment, and its abbreviation, which is empty for zones
+    /// that only go by their offset.
+    pub fn offset(&self, date_time: &DateTime) -> (i64, String) {
+        let info = self.rules.to_offset_info(timestamp(&date_time.seconds));
+        let abbreviation = info.abbreviation();
+
+        let abbreviation = if abbreviation.starts_with(['+', '-']) {
+            String::new()
+        } else {
+            abbreviation.to_owned()
+        };
+
+        (i64::from(info.offset().seconds()), abbreviation)
+    }
+
+    /// The moment a local time, counted as if it were in UTC, happens in this zone.
+    pub fn resolve(&self, local: &DateTime) -> Local {
+        let civil = Offset::UTC.to_datetime(timestamp(&local.seconds));
+
+        match self.rules.to_ambiguous_timestamp(civil).offset() {
+            AmbiguousOffset::Unambiguous { offset } => Local::Unique(DateTime {
+                seconds: &local.seconds - Rational::from(offset.seconds()),
+                date: false,
+            }),
+            // The offset before the clocks go back is the earlier of the two matching moments.
+            AmbiguousOffset::Fold { before, after } => {
+                Local::Ambiguous(i64::from(before.seconds()), i64::from(after.seconds()))
+            }
+            AmbiguousOffset::Gap { .. } => Local::Nonexistent,
+        }
+    }
+
+    /// Writes a moment in the local time of this zone, like `2026-10-17 05:30:00 EDT (UTC-04:00)`.
+    pub fn display(&self, date_time: &DateTime) -> String {
+        let (offset, abbreviation) = self.offset(date_time);
+
+        let local = DateTime {
+            seconds: &date_time.seconds + Rational::from(offset),
+            date: false,
+        };
+
+        let local = format!("{} {}", local.display_date(), local.display_time());
+
+        match abbreviation.as_str() {
+            "UTC" => format!("{local} UTC"),
+            "" => format!("{local} UTC{}", display_offset(offset)),
+            abbreviation => format!("{local} {abbreviation} (UTC{})", display_offset(offset)),
+        }
+    }
+}
+
+/// The whole second a moment falls in, as far as the database goes.
+fn timestamp(seconds: &Rational) -> Timestamp {
+    let second = i64::try_from(&seconds.floor()).unwrap_or(i64::MAX);
+
+    Timestamp::from_second(second).unwrap_or(if second < 0 {
+        Timestamp::MIN
+    } else {
+        Timestamp::MAX
+    })
+}
+
+/// Writes an offset from UTC in seconds, like `-04:00` or `+05:45`.
+pub fn display_offset(offset: i64) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+
+    format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}